@@ -1,6 +1,5 @@
 use anyhow::Result;
-use base64::Engine;
-use cdp_html_shot::Browser;
+use cdp_html_shot::{Browser, CaptureOptions};
 use shindan_maker::{ShindanClient, ShindanDomain};
 use std::fs;
 
@@ -18,9 +17,9 @@ async fn main() -> Result<()> {
 
     let browser = Browser::new_with_head().await?;
 
-    let base64 = browser.capture_html(&html_str, "#title_and_result").await?;
-
-    let img_data = base64::prelude::BASE64_STANDARD.decode(base64)?;
+    let img_data = browser
+        .capture_html_bytes(&html_str, "#title_and_result", CaptureOptions::new())
+        .await?;
     fs::write("test0.jpeg", img_data)?;
 
     Ok(())
@@ -1,7 +1,6 @@
 use std::fs;
-use base64::Engine;
 use anyhow::Result;
-use cdp_html_shot::Browser;
+use cdp_html_shot::{Browser, CaptureOptions};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -16,9 +15,9 @@ async fn main() -> Result<()> {
         </html>
     "#;
 
-    let base64 = browser.capture_html(HTML, "html").await?;
-
-    let img_data = base64::prelude::BASE64_STANDARD.decode(base64)?;
+    let img_data = browser
+        .capture_html_bytes(HTML, "html", CaptureOptions::new())
+        .await?;
 
     let dir = std::env::current_dir()?.join("cache");
     fs::create_dir_all(&dir)?;
@@ -18,7 +18,7 @@ async fn main() {
 
     for _ in 0..10 {
         let handle = task::spawn(async move {
-            let browser = Browser::instance().await;
+            let browser = Browser::instance().await.unwrap();
             let tab = browser.new_tab().await.unwrap();
             tab.close().await.unwrap();
         });
@@ -1,6 +1,4 @@
-use tokio::time;
 use anyhow::Result;
-use base64::Engine;
 use cdp_html_shot::Browser;
 
 #[tokio::main]
@@ -26,12 +24,8 @@ async fn main() -> Result<()> {
     tab.set_content(HTML).await?;
 
     let element = tab.find_element("html").await?;
-    let base64 = element.screenshot().await?;
+    element.save_to("test0.jpeg").await?;
     tab.close().await?;
 
-    let img_data = base64::prelude::BASE64_STANDARD.decode(base64)?;
-    std::fs::write("test0.jpeg", img_data)?;
-
-    time::sleep(time::Duration::from_secs(5)).await;
     Ok(())
 }
\ No newline at end of file
@@ -7,17 +7,28 @@ use log::error;
 use std::sync::Arc;
 use serde_json::json;
 use std::process::Child;
+use std::time::Duration;
 use tokio::sync::OnceCell;
+use futures_util::Stream;
 use temp_dir::CustomTempDir;
-use anyhow::{Context, Result};
 use browser_config::BrowserConfig;
+use tokio_tungstenite::tungstenite::http::HeaderMap;
 
 use crate::tab::Tab;
+use crate::CdpEvent;
+use crate::Viewport;
+use crate::cdp_event;
+use crate::Selector;
+use crate::PdfOptions;
 use crate::CaptureOptions;
+use crate::CaptureReport;
+use crate::ConnectOptions;
+use crate::general_utils;
 use crate::transport::Transport;
 use crate::general_utils::next_id;
-use crate::transport_actor::TransportResponse;
-use crate::browser::browser_builder::BrowserBuilder;
+use crate::error::{Error, Result};
+
+pub use browser_builder::BrowserBuilder;
 
 /// The global browser instance.
 static mut BROWSER: OnceCell<Arc<Browser>> = OnceCell::const_new();
@@ -29,7 +40,9 @@ struct Process(pub Child, pub CustomTempDir);
 #[derive(Debug)]
 pub struct Browser {
     transport: Arc<Transport>,
-    process: Process,
+    /// The browser process, unless the browser was started by someone else.
+    process: Option<Process>,
+    viewport: Option<Viewport>,
     is_closed: bool,
 }
 
@@ -64,16 +77,133 @@ impl Browser {
             .await
     }
 
+    /**
+    Create a builder to configure the browser before launching it.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::builder()
+            .viewport(1280, 720)
+            .build()
+            .await?;
+        Ok(())
+    }
+    ```
+    */
+    pub fn builder() -> BrowserBuilder {
+        BrowserBuilder::new()
+    }
+
+    /**
+    Connect to an already running browser by its DevTools WebSocket URL.
+
+    Closing a connected browser only disconnects from it; the browser keeps running.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::connect("ws://127.0.0.1:9222/devtools/browser/<id>").await?;
+        let base64 = browser.capture_html("<h1>Hello world!</h1>", "h1").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn connect(ws_url: &str) -> Result<Self> {
+        Self::connect_with_options(ws_url, ConnectOptions::new()).await
+    }
+
+    /// Connect to an already running browser by its DevTools WebSocket URL, with the given options.
+    pub async fn connect_with_options(ws_url: &str, options: ConnectOptions) -> Result<Self> {
+        let headers = options.header_map()?;
+        Self::connect_with_headers(ws_url, headers, options.command_timeout).await
+    }
+
+    /// Connect to a browser by its DevTools WebSocket URL with already validated headers.
+    async fn connect_with_headers(ws_url: &str, headers: HeaderMap, command_timeout: Duration) -> Result<Self> {
+        let transport = Transport::new(ws_url, headers, command_timeout).await?;
+
+        Ok(Self {
+            transport: Arc::new(transport),
+            process: None,
+            viewport: None,
+            is_closed: false,
+        })
+    }
+
+    /**
+    Connect to an already running browser by its DevTools HTTP endpoint,
+    e.g. a browser started with `--remote-debugging-port=9222`.
+
+    The WebSocket URL is looked up from `/json/version`. Only `http://` URLs are supported.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::connect_http("http://127.0.0.1:9222").await?;
+        let base64 = browser.capture_html("<h1>Hello world!</h1>", "h1").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn connect_http(url: &str) -> Result<Self> {
+        Self::connect_http_with_options(url, ConnectOptions::new()).await
+    }
+
+    /**
+    Connect to an already running browser by its DevTools HTTP endpoint, with the given options.
+
+    The headers are sent with both the `/json/version` request and the WebSocket handshake.
+    The lookup fails with [`Error::Timeout`] if it takes longer than the command timeout.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, ConnectOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let options = ConnectOptions::new().with_header("Authorization", "Bearer <token>");
+        let browser = Browser::connect_http_with_options("http://chrome:9222", options).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn connect_http_with_options(url: &str, options: ConnectOptions) -> Result<Self> {
+        let headers = options.header_map()?;
+
+        let discover = browser_utils::discover_websocket_url(url, &headers);
+        let ws_url = tokio::time::timeout(options.command_timeout, discover)
+            .await
+            .map_err(|_| Error::Timeout { method: "/json/version".to_string() })??;
+        Self::connect_with_headers(&ws_url, headers, options.command_timeout).await
+    }
+
     /// Create browser instance with custom configuration.
     async fn create_browser(config: BrowserConfig) -> Result<Self> {
         let mut child = browser_utils::spawn_chrome_process(&config)?;
-        let ws_url = browser_utils::get_websocket_url(
-            child.stderr.take().context("Failed to get stderr")?
-        ).await?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| Error::BrowserLaunch("Failed to get stderr".to_string()))?;
+        let ws_url = browser_utils::get_websocket_url(stderr).await?;
 
         Ok(Self {
-            transport: Arc::new(Transport::new(&ws_url).await?),
-            process: Process(child, config.temp_dir),
+            transport: Arc::new(Transport::new(&ws_url, HeaderMap::new(), config.command_timeout).await?),
+            process: Some(Process(child, config.temp_dir)),
+            viewport: config.viewport,
             is_closed: false,
         })
     }
@@ -95,7 +225,37 @@ impl Browser {
     ```
     */
     pub async fn new_tab(&self) -> Result<Tab> {
-        Tab::new(self.transport.clone()).await
+        Tab::new(self.transport.clone(), self.viewport).await
+    }
+
+    /**
+    Subscribe to every CDP event of the browser and all of its tabs.
+
+    Browser-level events have no [`session_id`](CdpEvent::session_id).
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use futures::StreamExt;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let mut events = browser.events();
+
+        let tab = browser.new_tab().await?;
+        tab.goto("https://www.rust-lang.org/").await?;
+
+        while let Some(event) = events.next().await {
+            println!("{:?} {}", event.session_id, event.method);
+        }
+        Ok(())
+    }
+    ```
+    */
+    pub fn events(&self) -> impl Stream<Item = CdpEvent> + Send + Unpin + 'static {
+        cdp_event::into_stream(self.transport.subscribe(), |_| true)
     }
 
     /**
@@ -105,24 +265,23 @@ impl Browser {
     Only in headless mode, otherwise it will close the entire browser.
     */
     pub async fn close_init_tab(&self) -> Result<()> {
-        let TransportResponse::Response(res) = self.transport.send(json!({
+        let res = self.transport.call(json!({
             "id": next_id(),
             "method": "Target.getTargets",
             "params": {}
-        })).await? else { panic!() };
+        })).await?;
 
-        let target_id = res
-            .result["targetInfos"]
+        let Some(target_id) = res["targetInfos"]
             .as_array()
-            .unwrap()
+            .ok_or_else(|| Error::protocol("Failed to get targetInfos"))?
             .iter()
-            .find(|info| {
-                info["type"].as_str().unwrap() == "page"
-            }).unwrap()["targetId"]
-            .as_str()
-            .unwrap();
+            .find(|info| info["type"] == "page")
+            .and_then(|info| info["targetId"].as_str())
+        else {
+            return Ok(());
+        };
 
-        self.transport.send(json!({
+        self.transport.call(json!({
             "id": next_id(),
             "method": "Target.closeTarget",
             "params": {
@@ -142,7 +301,7 @@ impl Browser {
 
     # Arguments
     - `html`: The HTML content
-    - `selector`: The CSS selector of the element to capture
+    - `selector`: The CSS selector (or any [`Selector`]) of the element to capture
 
     [`capture_html_with_options`]: struct.Browser.html#method.capture_html_with_options
 
@@ -159,16 +318,8 @@ impl Browser {
     }
     ```
     */
-    pub async fn capture_html(&self, html: &str, selector: &str) -> Result<String> {
-        let tab = self.new_tab().await?;
-
-        tab.set_content(html).await?;
-
-        let element = tab.find_element(selector).await?;
-        let base64 = element.screenshot().await?;
-
-        tab.close().await?;
-        Ok(base64)
+    pub async fn capture_html(&self, html: &str, selector: impl Into<Selector>) -> Result<String> {
+        self.capture_html_with_options(html, selector, CaptureOptions::new()).await
     }
 
     /**
@@ -176,19 +327,19 @@ impl Browser {
 
     # Arguments
     - `html`: The HTML content
-    - `selector`: The CSS selector of the element to capture
+    - `selector`: The CSS selector (or any [`Selector`]) of the element to capture
     - `options`: Configuration options for the capture
 
     # Example
     ```no_run
-    use cdp_html_shot::{Browser, CaptureOptions};
+    use cdp_html_shot::{Browser, CaptureOptions, ImageFormat};
     use anyhow::Result;
 
     #[tokio::main]
     async fn main() -> Result<()> {
         let browser = Browser::new().await?;
         let options = CaptureOptions::new()
-            .with_raw_png(true);
+            .with_format(ImageFormat::Webp { quality: 80 });
 
         let base64 = browser
             .capture_html_with_options(
@@ -203,30 +354,320 @@ impl Browser {
     pub async fn capture_html_with_options(
         &self,
         html: &str,
-        selector: &str,
+        selector: impl Into<Selector>,
         options: CaptureOptions,
     ) -> Result<String> {
-        let tab = self.new_tab().await?;
+        let report = self.capture_html_report(html, selector, options).await?;
+        Ok(report.image)
+    }
 
-        tab.set_content(html).await?;
+    /**
+    Capture a screenshot of an HTML element, together with the console messages,
+    exceptions and log entries the page produced while loading.
 
-        let element = tab.find_element(selector).await?;
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, CaptureOptions};
+    use anyhow::Result;
 
-        let base64 = if options.raw_png {
-            element.raw_screenshot().await?
-        } else {
-            element.screenshot().await?
-        };
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let report = browser
+            .capture_html_report(
+                "<h1>Hello world!</h1><script>missing()</script>",
+                "h1",
+                CaptureOptions::new()
+            ).await?;
+
+        for exception in report.exceptions() {
+            eprintln!("{}", exception.text);
+        }
+        Ok(())
+    }
+    ```
+    */
+    pub async fn capture_html_report(
+        &self,
+        html: &str,
+        selector: impl Into<Selector>,
+        options: CaptureOptions,
+    ) -> Result<CaptureReport> {
+        self.with_tab(options.timeout, async move |tab| {
+            let mut recorder = tab.record_diagnostics().await?;
+            tab.apply_options(&options).await?;
+
+            tab.set_content_with_options(html, options.content_options()).await?;
+
+            let selector = selector.into();
+            if let Some(wait) = options.wait {
+                tab.wait_for_selector(selector.clone(), wait).await?;
+            }
+            let messages = options.check_messages(recorder.take())?;
+
+            let element = tab.find_element(selector).await?;
+            let image = element
+                .take_screenshot_with_config(options.screenshot_config())
+                .await?;
+
+            Ok(CaptureReport { image, messages })
+        }).await
+    }
+
+    /**
+    Capture a screenshot of every element matching a selector.
+
+    The page is loaded once, and the screenshots are returned in document order
+    as base64-encoded image data.
+
+    # Arguments
+    - `html`: The HTML content
+    - `selector`: The CSS selector (or any [`Selector`]) of the elements to capture
+    - `options`: Configuration options for the capture
 
-        tab.close().await?;
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, CaptureOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let cards = browser
+            .capture_html_all(
+                r#"<div class="card">A</div><div class="card">B</div>"#,
+                ".card",
+                CaptureOptions::new()
+            ).await?;
+        assert_eq!(cards.len(), 2);
+        Ok(())
+    }
+    ```
+    */
+    pub async fn capture_html_all(
+        &self,
+        html: &str,
+        selector: impl Into<Selector>,
+        options: CaptureOptions,
+    ) -> Result<Vec<String>> {
+        self.with_tab(options.timeout, async move |tab| {
+            let mut recorder = tab.record_diagnostics().await?;
+            tab.apply_options(&options).await?;
 
-        Ok(base64)
+            tab.set_content_with_options(html, options.content_options()).await?;
+
+            let selector = selector.into();
+            if let Some(wait) = options.wait {
+                tab.wait_for_selector(selector.clone(), wait).await?;
+            }
+            options.check_messages(recorder.take())?;
+
+            let mut images = Vec::new();
+            for element in tab.find_elements(selector).await? {
+                images.push(
+                    element
+                        .take_screenshot_with_config(options.screenshot_config())
+                        .await?
+                );
+            }
+            Ok(images)
+        }).await
+    }
+
+    /**
+    Capture a screenshot of an HTML element and return the decoded image bytes.
+
+    # Arguments
+    - `html`: The HTML content
+    - `selector`: The CSS selector (or any [`Selector`]) of the element to capture
+    - `options`: Configuration options for the capture
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, CaptureOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let jpeg = browser
+            .capture_html_bytes("<h1>Hello world!</h1>", "h1", CaptureOptions::new())
+            .await?;
+        std::fs::write("h1.jpeg", jpeg)?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn capture_html_bytes(
+        &self,
+        html: &str,
+        selector: impl Into<Selector>,
+        options: CaptureOptions,
+    ) -> Result<Vec<u8>> {
+        let base64 = self.capture_html_with_options(html, selector, options).await?;
+        general_utils::decode_base64(&base64)
+    }
+
+    /// Capture a screenshot of an HTML element and decode it into an image.
+    #[cfg(feature = "image")]
+    pub async fn capture_html_image(
+        &self,
+        html: &str,
+        selector: impl Into<Selector>,
+        options: CaptureOptions,
+    ) -> Result<image::DynamicImage> {
+        let bytes = self.capture_html_bytes(html, selector, options).await?;
+        general_utils::decode_image(&bytes)
+    }
+
+    /**
+    Capture a screenshot of a whole HTML page.
+
+    The page is captured in full, including content outside the viewport.
+
+    # Arguments
+    - `html`: The HTML content
+    - `options`: Configuration options for the capture
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, CaptureOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let base64 = browser
+            .capture_page("<h1>Hello world!</h1>", CaptureOptions::new())
+            .await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn capture_page(&self, html: &str, options: CaptureOptions) -> Result<String> {
+        let report = self.capture_page_report(html, options).await?;
+        Ok(report.image)
+    }
+
+    /// Capture a screenshot of a whole HTML page, together with the console messages,
+    /// exceptions and log entries the page produced while loading.
+    pub async fn capture_page_report(&self, html: &str, options: CaptureOptions) -> Result<CaptureReport> {
+        self.with_tab(options.timeout, async move |tab| {
+            let mut recorder = tab.record_diagnostics().await?;
+            tab.apply_options(&options).await?;
+
+            tab.set_content_with_options(html, options.content_options()).await?;
+            let messages = options.check_messages(recorder.take())?;
+
+            let image = tab.screenshot_full_page(options.screenshot_config()).await?;
+            Ok(CaptureReport { image, messages })
+        }).await
+    }
+
+    /**
+    Navigate to a URL and capture a screenshot of the whole page.
+
+    Waits for the page's `load` event before capturing,
+    see [`CaptureOptions::with_navigation`] to wait for another event.
+
+    # Arguments
+    - `url`: The URL to capture
+    - `options`: Configuration options for the capture
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, CaptureOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let base64 = browser
+            .capture_url("https://www.rust-lang.org/", CaptureOptions::new())
+            .await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn capture_url(&self, url: &str, options: CaptureOptions) -> Result<String> {
+        let report = self.capture_url_report(url, options).await?;
+        Ok(report.image)
+    }
+
+    /// Navigate to a URL and capture a screenshot of the whole page, together with the
+    /// console messages, exceptions and log entries the page produced while loading.
+    pub async fn capture_url_report(&self, url: &str, options: CaptureOptions) -> Result<CaptureReport> {
+        self.with_tab(options.timeout, async move |tab| {
+            let mut recorder = tab.record_diagnostics().await?;
+            tab.apply_options(&options).await?;
+
+            tab.goto_with_options(url, options.navigation).await?;
+            let messages = options.check_messages(recorder.take())?;
+
+            let image = tab.screenshot_full_page(options.screenshot_config()).await?;
+            Ok(CaptureReport { image, messages })
+        }).await
+    }
+
+    /**
+    Render HTML content as a PDF.
+
+    Returns the raw PDF bytes.
+
+    # Arguments
+    - `html`: The HTML content
+    - `options`: Configuration options for the PDF
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, PdfOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let options = PdfOptions::new()
+            .with_landscape(true)
+            .with_footer_template(r#"<span class="pageNumber"></span>"#);
+
+        let pdf = browser.render_pdf("<h1>Report</h1>", options).await?;
+        std::fs::write("report.pdf", pdf)?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn render_pdf(&self, html: &str, options: PdfOptions) -> Result<Vec<u8>> {
+        self.with_tab(None, async move |tab| {
+            tab.set_content(html).await?;
+            tab.pdf(options).await
+        }).await
+    }
+
+    /// Run `steps` on a new tab, then close the tab whether or not they succeeded.
+    ///
+    /// The tab is created before the deadline starts, so it is closed even when the deadline
+    /// passes and the remaining steps are cancelled.
+    async fn with_tab<T>(
+        &self,
+        timeout: Option<Duration>,
+        steps: impl AsyncFnOnce(&Tab) -> Result<T>,
+    ) -> Result<T> {
+        let tab = self.new_tab().await?;
+
+        let result = general_utils::with_deadline(timeout, steps(&tab)).await;
+        let closed = tab.close().await;
+
+        let value = result?;
+        closed?;
+        Ok(value)
     }
 
     /**
     Close the browser.
 
     This will kill the browser process and clean up temporary files.
+    A browser connected with [`connect`](Self::connect) or [`connect_http`](Self::connect_http)
+    is only disconnected from and keeps running.
 
     Normally, this method does not need to be called manually,
     because it will be called automatically when the `Browser` instance is destroyed.
@@ -249,16 +690,19 @@ impl Browser {
             return Ok(());
         }
 
-        Arc::get_mut(&mut self.transport)
-            .unwrap()
-            .shutdown();
+        let Some(process) = &mut self.process else {
+            self.transport.disconnect();
+            self.is_closed = true;
+            return Ok(());
+        };
+
+        self.transport.shutdown();
 
-        self.process.0
+        process.0
             .kill()
-            .and_then(|_| self.process.0.wait())
-            .context("Failed to kill the browser process")?;
+            .and_then(|_| process.0.wait())?;
 
-        self.process.1
+        process.1
             .cleanup()?;
 
         self.is_closed = true;
@@ -277,6 +721,9 @@ impl Browser {
     The browser will be automatically closed
     when all references are dropped or when the program exits.
 
+    Returns an error if the browser could not be launched;
+    a later call will try to launch it again.
+
     # Example
     ```no_run
     use cdp_html_shot::Browser;
@@ -284,7 +731,7 @@ impl Browser {
 
     #[tokio::main]
     async fn main() -> Result<()> {
-        let browser = Browser::instance().await;
+        let browser = Browser::instance().await?;
         let tab = browser.new_tab().await?;
 
         Browser::close_instance();
@@ -292,17 +739,17 @@ impl Browser {
     }
     ```
     */
-    pub async fn instance() -> Arc<Browser> {
+    pub async fn instance() -> Result<Arc<Browser>> {
         unsafe {
             let browser = BROWSER
-                .get_or_init(|| async {
-                    let browser = Browser::new().await.unwrap();
-                    browser.close_init_tab().await.unwrap();
-                    Arc::new(browser)
+                .get_or_try_init(|| async {
+                    let browser = Browser::new().await?;
+                    browser.close_init_tab().await?;
+                    Ok::<_, Error>(Arc::new(browser))
                 })
-                .await;
+                .await?;
 
-            browser.clone()
+            Ok(browser.clone())
         }
     }
 
@@ -328,3 +775,96 @@ impl Drop for Browser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Serve a minimal DevTools endpoint that reports the method of every command it receives
+    /// and answers `failing` with an error.
+    async fn fake_browser(failing: &'static str) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/devtools/browser/fake", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let command: serde_json::Value = serde_json::from_str(&text).unwrap();
+                let method = command["method"].as_str().unwrap_or_default();
+                let _ = tx.send(method.to_string());
+
+                let result = match method {
+                    "Target.createTarget" => {
+                        // A slow browser: creating the target takes longer than the capture deadline.
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        json!({ "targetId": "target" })
+                    }
+                    "Target.attachToTarget" => json!({ "sessionId": "session" }),
+                    _ => json!({}),
+                };
+
+                let mut reply = match method == failing {
+                    true => json!({ "id": command["id"], "error": { "code": -32000, "message": "Rejected" } }),
+                    false => json!({ "id": command["id"], "result": result }),
+                };
+                if let Some(session_id) = command.get("sessionId") {
+                    reply["sessionId"] = session_id.clone();
+                }
+                if ws.send(Message::Text(reply.to_string())).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        (url, rx)
+    }
+
+    // Dropping the browser waits for the transport to shut down, which needs a second worker.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn with_tab_closes_tab_after_deadline() {
+        let (url, mut methods) = fake_browser("").await;
+        let browser = Browser::connect(&url).await.unwrap();
+
+        let timeout = Duration::from_millis(10);
+        let result = browser
+            .with_tab(Some(timeout), async |_| {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(Error::CaptureTimeout { .. })));
+
+        let mut received = Vec::new();
+        while let Ok(method) = methods.try_recv() {
+            received.push(method);
+        }
+        assert_eq!(received, ["Target.createTarget", "Target.attachToTarget", "Target.closeTarget"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn new_tab_closes_target_when_viewport_fails() {
+        let (url, mut methods) = fake_browser("Emulation.setDeviceMetricsOverride").await;
+        let browser = Browser::connect(&url).await.unwrap();
+
+        let result = Tab::new(browser.transport.clone(), Some(Viewport::new(800, 600))).await;
+        assert!(matches!(result, Err(Error::Cdp { .. })));
+
+        let mut received = Vec::new();
+        while let Ok(method) = methods.try_recv() {
+            received.push(method);
+        }
+        assert_eq!(received, [
+            "Target.createTarget",
+            "Target.attachToTarget",
+            "Emulation.setDeviceMetricsOverride",
+            "Target.closeTarget",
+        ]);
+    }
+}
@@ -1,34 +1,56 @@
-use anyhow::Result;
+use std::time::Duration;
 
 use crate::Browser;
+use crate::Viewport;
+use crate::error::Result;
+use crate::transport::DEFAULT_COMMAND_TIMEOUT;
 use crate::browser::browser_config::BrowserConfig;
 
 /// Builder for configuring and creating Browser instances.
 pub struct BrowserBuilder {
-    config: BrowserConfig,
+    headless: bool,
+    viewport: Option<Viewport>,
+    command_timeout: Duration,
 }
 
 impl BrowserBuilder {
     /// Create a new BrowserBuilder with default configuration.
     pub fn new() -> Self {
         Self {
-            config: BrowserConfig::new()
-                .expect("Failed to create default browser config")
+            headless: true,
+            viewport: None,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
         }
     }
 
     /// Set whether the browser should run in headless mode.
     pub fn headless(mut self, headless: bool) -> Self {
-        self.config.headless = headless;
+        self.headless = headless;
         self
     }
 
-    /// Configure additional options here as needed.
-    // pub fn with_option(mut self, option: Option) -> Self { ... }
+    /// Set the default viewport size of the browser window and every new tab.
+    pub fn viewport(mut self, width: u32, height: u32) -> Self {
+        self.viewport = Some(Viewport::new(width, height));
+        self
+    }
+
+    /// Set how long to wait for the browser to answer a command (5 seconds by default).
+    ///
+    /// Tabs can override this with [`Tab::set_command_timeout`](crate::Tab::set_command_timeout).
+    pub fn command_timeout(mut self, timeout: Duration) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
 
     /// Build and launch the browser with the configured options.
     pub async fn build(self) -> Result<Browser> {
-        Browser::create_browser(self.config).await
+        let mut config = BrowserConfig::new()?;
+        config.headless = self.headless;
+        config.viewport = self.viewport;
+        config.command_timeout = self.command_timeout;
+
+        Browser::create_browser(config).await
     }
 }
 
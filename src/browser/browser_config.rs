@@ -1,12 +1,15 @@
 use std::net;
+use std::time::Duration;
 use which::which;
 use std::path::{Path, PathBuf};
 use rand::prelude::SliceRandom;
-use anyhow::{anyhow, Context, Result};
 
 #[cfg(windows)]
 use winreg::{RegKey, enums::HKEY_LOCAL_MACHINE};
 
+use crate::Viewport;
+use crate::error::{Error, Result};
+use crate::transport::DEFAULT_COMMAND_TIMEOUT;
 use crate::browser::temp_dir::CustomTempDir;
 
 static DEFAULT_ARGS: [&str; 37] = [
@@ -70,6 +73,8 @@ static DEFAULT_ARGS: [&str; 37] = [
 pub(crate) struct BrowserConfig {
     debug_port: u16,
     pub(crate) headless: bool,
+    pub(crate) viewport: Option<Viewport>,
+    pub(crate) command_timeout: Duration,
     pub(crate) temp_dir: CustomTempDir,
     pub(crate) executable_path: PathBuf,
 }
@@ -80,10 +85,12 @@ impl BrowserConfig {
 
         Ok(Self {
             headless: true,
+            viewport: None,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
             executable_path: default_executable()?,
-            debug_port: get_available_port().context("Failed to get available port")?,
-            temp_dir: CustomTempDir::new(temp_dir, "cdp-html-shot")
-                .context("Failed to create custom temporary directory")?,
+            debug_port: get_available_port()
+                .ok_or_else(|| Error::BrowserLaunch("Failed to get available port".to_string()))?,
+            temp_dir: CustomTempDir::new(temp_dir, "cdp-html-shot")?,
         })
     }
 
@@ -97,6 +104,9 @@ impl BrowserConfig {
         if self.headless {
             args.push("--headless".to_string());
         }
+        if let Some(viewport) = self.viewport {
+            args.push(format!("--window-size={},{}", viewport.width, viewport.height));
+        }
 
         args
     }
@@ -168,7 +178,7 @@ fn default_executable() -> Result<PathBuf> {
         }
     }
 
-    Err(anyhow!("Could not auto detect a chrome executable"))
+    Err(Error::BrowserLaunch("Could not auto detect a chrome executable".to_string()))
 }
 
 #[cfg(windows)]
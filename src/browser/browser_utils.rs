@@ -1,8 +1,12 @@
 use regex::Regex;
-use anyhow::{Context, Result};
+use serde_json::Value;
+use tokio::net::TcpStream;
 use std::io::{BufRead, BufReader};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use std::process::{ChildStderr, Command, Stdio};
+use tokio_tungstenite::tungstenite::http::HeaderMap;
 
+use crate::error::{Error, Result};
 use crate::browser::browser_config::BrowserConfig;
 
 pub(crate) fn spawn_chrome_process(config: &BrowserConfig) -> Result<std::process::Child> {
@@ -15,7 +19,7 @@ pub(crate) fn spawn_chrome_process(config: &BrowserConfig) -> Result<std::proces
         .args(config.get_browser_args())
         .stderr(Stdio::piped())
         .spawn()
-        .context("Failed to spawn a Chrome process")
+        .map_err(|e| Error::BrowserLaunch(format!("Failed to spawn a Chrome process: {e}")))
 }
 
 #[cfg(windows)]
@@ -29,12 +33,13 @@ pub(crate) async fn get_websocket_url(stderr: ChildStderr) -> Result<String> {
     let reader = BufReader::new(stderr);
     ws_url_from_reader(reader)
         .await?
-        .context("Failed to get ws url")
+        .ok_or_else(|| Error::BrowserLaunch("Failed to get ws url".to_string()))
 }
 
 async fn ws_url_from_reader(reader: BufReader<ChildStderr>) -> Result<Option<String>>
 {
-    let re = Regex::new(r"listening on (.*/devtools/browser/.*)$")?;
+    let re = Regex::new(r"listening on (.*/devtools/browser/.*)$")
+        .expect("valid regex");
 
     let extract = |text: &str| -> Option<String> {
         let caps = re.captures(text);
@@ -48,4 +53,89 @@ async fn ws_url_from_reader(reader: BufReader<ChildStderr>) -> Result<Option<Str
         }
     }
     Ok(None)
-}
\ No newline at end of file
+}
+
+/// Ask the DevTools HTTP endpoint of a running browser (`http://host:port`) for its WebSocket URL.
+pub(crate) async fn discover_websocket_url(http_url: &str, headers: &HeaderMap) -> Result<String> {
+    let authority = http_url
+        .strip_prefix("http://")
+        .and_then(|rest| rest.split('/').next())
+        .filter(|authority| !authority.is_empty())
+        .ok_or_else(|| Error::InvalidArgument(format!("Expected an http://host:port URL: {http_url}")))?;
+
+    let authority = match authority.rsplit_once(':') {
+        Some((_, port)) if !port.ends_with(']') => authority.to_string(),
+        _ => format!("{authority}:80"),
+    };
+    let addr = tokio::net::lookup_host(&authority)
+        .await?
+        .next()
+        .ok_or_else(|| Error::InvalidArgument(format!("Failed to resolve {authority}")))?;
+
+    // The browser only answers requests whose Host header is an IP address or localhost.
+    let mut request = format!("GET /json/version HTTP/1.0\r\nHost: {addr}\r\nConnection: close\r\n").into_bytes();
+    for (name, value) in headers {
+        request.extend_from_slice(name.as_str().as_bytes());
+        request.extend_from_slice(b": ");
+        request.extend_from_slice(value.as_bytes());
+        request.extend_from_slice(b"\r\n");
+    }
+    request.extend_from_slice(b"\r\n");
+
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.write_all(&request).await?;
+
+    let response = read_http_response(&mut stream).await?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| Error::protocol("Malformed /json/version response"))?;
+
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(Error::protocol(format!("/json/version answered with status {status}")));
+    }
+    if header_value(head, "transfer-encoding").is_some_and(|value| !value.eq_ignore_ascii_case("identity")) {
+        return Err(Error::protocol("/json/version answered with an unsupported Transfer-Encoding"));
+    }
+
+    let version: Value = serde_json::from_str(body)?;
+    version["webSocketDebuggerUrl"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| Error::protocol("Failed to get webSocketDebuggerUrl"))
+}
+
+/// Read an HTTP response, stopping after `Content-Length` bytes of body if it is given.
+///
+/// The request is sent as HTTP/1.0, so the body is never chunked and otherwise ends when the connection closes.
+async fn read_http_response(stream: &mut TcpStream) -> Result<String> {
+    let mut response = Vec::new();
+    let mut buf = [0; 4096];
+
+    loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        response.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&response);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            continue;
+        };
+        let content_length = header_value(head, "content-length").and_then(|value| value.parse::<usize>().ok());
+        if content_length.is_some_and(|len| body.len() >= len) {
+            break;
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+/// The trimmed value of the first header called `name` in the head of an HTTP response.
+fn header_value<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
@@ -2,7 +2,7 @@ use std::fs;
 use chrono::Local;
 use rand::{thread_rng, Rng};
 use std::path::{Path, PathBuf};
-use anyhow::{Context, Result};
+use std::io::Result;
 
 #[derive(Debug)]
 pub(crate) struct CustomTempDir {
@@ -22,14 +22,12 @@ impl CustomTempDir {
     pub(crate) fn new(base_path: impl AsRef<Path>, prefix: &str) -> Result<Self> {
         let base_path = base_path.as_ref();
 
-        fs::create_dir_all(base_path)
-            .context("Failed to create base directory")?;
+        fs::create_dir_all(base_path)?;
 
         let unique_name = generate_unique_name(prefix);
         let full_path = base_path.join(unique_name);
 
-        fs::create_dir(&full_path)
-            .context("Failed to create temporary directory")?;
+        fs::create_dir(&full_path)?;
 
         Ok(Self { path: full_path, is_cleaned: false })
     }
@@ -43,8 +41,7 @@ impl CustomTempDir {
             return Ok(());
         }
 
-        fs::remove_dir_all(&self.path)
-            .context("Failed to clean up temporary directory")?;
+        fs::remove_dir_all(&self.path)?;
 
         self.is_cleaned = true;
         Ok(())
@@ -1,7 +1,46 @@
+use std::time::Duration;
+
+use crate::ReadyOptions;
+use crate::MediaEmulation;
+use crate::NavigateOptions;
+use crate::SetContentOptions;
+use crate::{ConsoleMessage, Error, MessageSource, Result};
+use crate::{BoxKind, ImageFormat, Insets, Rgba, ScreenshotConfig, Viewport, WaitOptions};
+
 /// Configuration options for HTML capture.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct CaptureOptions {
-    pub(crate) raw_png: bool,
+    pub(crate) format: ImageFormat,
+    pub(crate) device_scale_factor: Option<f64>,
+    pub(crate) viewport: Option<Viewport>,
+    pub(crate) transparent_background: bool,
+    pub(crate) box_kind: BoxKind,
+    pub(crate) padding: Insets,
+    pub(crate) wait: Option<WaitOptions>,
+    pub(crate) media: Option<MediaEmulation>,
+    pub(crate) ready: ReadyOptions,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) fail_on_js_error: bool,
+    pub(crate) navigation: NavigateOptions,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Jpeg { quality: 90 },
+            device_scale_factor: None,
+            viewport: None,
+            transparent_background: false,
+            box_kind: BoxKind::default(),
+            padding: Insets::default(),
+            wait: None,
+            media: None,
+            ready: ReadyOptions::default(),
+            timeout: None,
+            fail_on_js_error: false,
+            navigation: NavigateOptions::default(),
+        }
+    }
 }
 
 impl CaptureOptions {
@@ -10,9 +49,155 @@ impl CaptureOptions {
         Self::default()
     }
 
+    /// Set the image format (JPEG with quality 90 by default).
+    pub fn with_format(mut self, format: ImageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Set whether to use a raw PNG format (true) or JPEG (false).
-    pub fn with_raw_png(mut self, raw: bool) -> Self {
-        self.raw_png = raw;
+    #[deprecated(note = "use `with_format` instead")]
+    pub fn with_raw_png(self, raw: bool) -> Self {
+        self.with_format(if raw {
+            ImageFormat::Png
+        } else {
+            ImageFormat::Jpeg { quality: 90 }
+        })
+    }
+
+    /// Set the device scale factor, e.g. `2.0` for Retina-quality images.
+    ///
+    /// Selectors and layout stay in CSS pixels; only the output resolution is scaled.
+    pub fn with_device_scale_factor(mut self, factor: f64) -> Self {
+        self.device_scale_factor = Some(factor);
+        self
+    }
+
+    /// Set the viewport to render the page in, e.g. to pick a responsive breakpoint.
+    ///
+    /// A device scale factor set with [`with_device_scale_factor`](Self::with_device_scale_factor)
+    /// takes precedence over [`Viewport::scale`].
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Set whether to capture with a transparent background instead of white.
+    ///
+    /// Requires a format with an alpha channel (PNG or WebP);
+    /// capturing as JPEG fails with [`Error::InvalidArgument`](crate::Error::InvalidArgument).
+    pub fn with_transparent_background(mut self, transparent: bool) -> Self {
+        self.transparent_background = transparent;
+        self
+    }
+
+    /// Set which CSS box of the element to capture (the border box by default).
+    pub fn with_box_kind(mut self, kind: BoxKind) -> Self {
+        self.box_kind = kind;
+        self
+    }
+
+    /// Set extra space to capture around the element, e.g. to include shadows or glows.
+    pub fn with_padding(mut self, padding: Insets) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Wait for the target selector to reach a state before capturing,
+    /// for pages that render asynchronously.
+    pub fn with_wait(mut self, wait: WaitOptions) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+
+    /// Set the conditions to wait for once the HTML content is set, e.g. web fonts
+    /// or a template-defined predicate such as `window.__READY__ === true`.
+    pub fn with_ready(mut self, ready: ReadyOptions) -> Self {
+        self.ready = ready;
+        self
+    }
+
+    /**
+    Emulate a CSS media type and media features while capturing.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, CaptureOptions, MediaEmulation};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let html = r#"
+            <style>
+                body { background: white; color: black; }
+                @media (prefers-color-scheme: dark) { body { background: #111; color: white; } }
+            </style>
+            <h1>Hello world!</h1>
+        "#;
+
+        let light = CaptureOptions::new().with_media(MediaEmulation::light());
+        let dark = CaptureOptions::new().with_media(MediaEmulation::dark());
+        let light = browser.capture_html_with_options(html, "h1", light).await?;
+        let dark = browser.capture_html_with_options(html, "h1", dark).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub fn with_media(mut self, media: MediaEmulation) -> Self {
+        self.media = Some(media);
+        self
+    }
+
+    /// Set how [`Browser::capture_url`](crate::Browser::capture_url) waits for the page to load.
+    pub fn with_navigation(mut self, navigation: NavigateOptions) -> Self {
+        self.navigation = navigation;
+        self
+    }
+
+    /// Set an overall deadline for the capture.
+    ///
+    /// When it passes, the remaining steps are cancelled, the tab is closed and the
+    /// capture fails with [`Error::CaptureTimeout`](crate::Error::CaptureTimeout).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
         self
     }
-}
\ No newline at end of file
+
+    /// Set whether an uncaught exception while loading the page fails the capture
+    /// with [`Error::JavaScript`](crate::Error::JavaScript).
+    pub fn with_fail_on_js_error(mut self, fail: bool) -> Self {
+        self.fail_on_js_error = fail;
+        self
+    }
+
+    /// Apply the JavaScript error policy to the messages recorded while loading the page.
+    pub(crate) fn check_messages(&self, messages: Vec<ConsoleMessage>) -> Result<Vec<ConsoleMessage>> {
+        if !self.fail_on_js_error {
+            return Ok(messages);
+        }
+
+        match messages.iter().find(|message| message.source == MessageSource::Exception) {
+            Some(exception) => Err(Error::JavaScript {
+                message: exception.text.clone(),
+                stack: exception.stack.clone(),
+            }),
+            None => Ok(messages),
+        }
+    }
+
+    /// The screenshot configuration these options resolve to.
+    pub(crate) fn screenshot_config(&self) -> ScreenshotConfig {
+        ScreenshotConfig {
+            format: self.format,
+            background: self.transparent_background.then_some(Rgba::TRANSPARENT),
+            box_kind: self.box_kind,
+            padding: self.padding,
+        }
+    }
+
+    /// The content options these options resolve to.
+    pub(crate) fn content_options(&self) -> SetContentOptions {
+        SetContentOptions::new().with_ready(self.ready.clone())
+    }
+}
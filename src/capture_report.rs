@@ -0,0 +1,146 @@
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::CdpEvent;
+use crate::general_utils;
+use crate::error::{Error, Result};
+
+/// Where a [`ConsoleMessage`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSource {
+    /// A `console.*` call in the page.
+    Console,
+    /// An uncaught JavaScript exception or unhandled promise rejection.
+    Exception,
+    /// A browser log entry, e.g. a failed network request or a CSP violation.
+    Log,
+}
+
+/// A console message, exception or log entry recorded while the page loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleMessage {
+    /// Where the message came from.
+    pub source: MessageSource,
+    /// The message level, e.g. `log`, `warning` or `error`.
+    pub level: String,
+    /// The message text.
+    pub text: String,
+    /// The JavaScript stack trace, for exceptions.
+    pub stack: Option<String>,
+    /// The URL of the script or resource the message refers to.
+    pub url: Option<String>,
+    /// The 1-based line number in `url`.
+    pub line: Option<u64>,
+}
+
+/// A screenshot together with the messages the page produced while loading.
+#[derive(Debug, Clone)]
+pub struct CaptureReport {
+    /// The base64-encoded image data.
+    pub image: String,
+    /// Console messages, exceptions and log entries, in the order they occurred.
+    pub messages: Vec<ConsoleMessage>,
+}
+
+impl CaptureReport {
+    /// The uncaught exceptions thrown while the page loaded.
+    pub fn exceptions(&self) -> impl Iterator<Item = &ConsoleMessage> {
+        self.messages
+            .iter()
+            .filter(|message| message.source == MessageSource::Exception)
+    }
+
+    /// Decode the image data.
+    pub fn image_bytes(&self) -> Result<Vec<u8>> {
+        general_utils::decode_base64(&self.image)
+    }
+}
+
+impl ConsoleMessage {
+    fn from_event(event: &CdpEvent) -> Option<Self> {
+        let params = &event.params;
+        match event.method.as_str() {
+            "Runtime.consoleAPICalled" => {
+                let text = params["args"]
+                    .as_array()
+                    .map(|args| args.iter().map(remote_object_text).collect::<Vec<_>>().join(" "))
+                    .unwrap_or_default();
+                let frame = &params["stackTrace"]["callFrames"][0];
+
+                Some(Self {
+                    source: MessageSource::Console,
+                    level: params["type"].as_str().unwrap_or("log").to_string(),
+                    text,
+                    stack: None,
+                    url: frame["url"].as_str().filter(|url| !url.is_empty()).map(String::from),
+                    line: frame["lineNumber"].as_u64().map(|line| line + 1),
+                })
+            }
+            "Runtime.exceptionThrown" => {
+                let details = &params["exceptionDetails"];
+                let Error::JavaScript { message, stack } = general_utils::js_exception(details) else {
+                    return None;
+                };
+
+                Some(Self {
+                    source: MessageSource::Exception,
+                    level: "error".to_string(),
+                    text: message,
+                    stack,
+                    url: details["url"].as_str().filter(|url| !url.is_empty()).map(String::from),
+                    line: details["lineNumber"].as_u64().map(|line| line + 1),
+                })
+            }
+            "Log.entryAdded" => {
+                let entry = &params["entry"];
+
+                Some(Self {
+                    source: MessageSource::Log,
+                    level: entry["level"].as_str().unwrap_or("info").to_string(),
+                    text: entry["text"].as_str().unwrap_or_default().to_string(),
+                    stack: None,
+                    url: entry["url"].as_str().map(String::from),
+                    line: entry["lineNumber"].as_u64().map(|line| line + 1),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A short text for a `Runtime.RemoteObject` console argument.
+fn remote_object_text(arg: &Value) -> String {
+    match &arg["value"] {
+        Value::String(value) => value.clone(),
+        Value::Null if arg["subtype"] == "null" => "null".to_string(),
+        Value::Null => arg["unserializableValue"]
+            .as_str()
+            .or_else(|| arg["description"].as_str())
+            .unwrap_or_else(|| arg["type"].as_str().unwrap_or_default())
+            .to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Records the console messages, exceptions and log entries of one tab.
+///
+/// The events come from a subscription to the tab's session alone, which never drops
+/// events, so no exception is lost however busy the rest of the browser is.
+pub(crate) struct DiagnosticsRecorder {
+    events: mpsc::UnboundedReceiver<CdpEvent>,
+}
+
+impl DiagnosticsRecorder {
+    pub(crate) fn new(events: mpsc::UnboundedReceiver<CdpEvent>) -> Self {
+        Self { events }
+    }
+
+    /// Take the messages recorded so far.
+    pub(crate) fn take(&mut self) -> Vec<ConsoleMessage> {
+        let mut messages = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            messages.extend(ConsoleMessage::from_event(&event));
+        }
+        messages
+    }
+}
@@ -0,0 +1,35 @@
+use serde_json::Value;
+use futures_util::Stream;
+use futures_util::stream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// A CDP event sent by the browser, e.g. `Page.loadEventFired` or `Runtime.consoleAPICalled`.
+#[derive(Debug, Clone)]
+pub struct CdpEvent {
+    /// The session of the tab that sent the event, or `None` for browser-level events.
+    pub session_id: Option<String>,
+    /// The event name, e.g. `Network.requestWillBeSent`.
+    pub method: String,
+    /// The event parameters.
+    pub params: Value,
+}
+
+/// Turn a subscription into a stream of the events that match `filter`.
+///
+/// Events missed because the subscriber fell behind are skipped with a warning.
+pub(crate) fn into_stream(
+    events: broadcast::Receiver<CdpEvent>,
+    filter: impl Fn(&CdpEvent) -> bool + Send + 'static,
+) -> impl Stream<Item = CdpEvent> + Send + Unpin + 'static {
+    Box::pin(stream::unfold((events, filter), |(mut events, filter)| async move {
+        loop {
+            match events.recv().await {
+                Ok(event) if filter(&event) => return Some((event, (events, filter))),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => log::warn!("Skipped {skipped} CDP events"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }))
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::http::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::error::{Error, Result};
+use crate::transport::DEFAULT_COMMAND_TIMEOUT;
+
+/// Configuration options for connecting to an already running browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// Extra HTTP headers sent with the `/json/version` request and the WebSocket handshake,
+    /// e.g. for authentication by a proxy in front of the browser.
+    pub headers: Vec<(String, String)>,
+    /// How long to wait for the browser to answer a command.
+    pub command_timeout: Duration,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            headers: Vec::new(),
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+        }
+    }
+}
+
+impl ConnectOptions {
+    /// Create new connect options with default values (no extra headers, 5 s command timeout).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an HTTP header to send when connecting.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set how long to wait for the browser to answer a command.
+    pub fn with_command_timeout(mut self, timeout: Duration) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
+
+    /// Validate the headers, rejecting names and values that are not allowed in HTTP,
+    /// such as values containing CR or LF.
+    pub(crate) fn header_map(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::InvalidArgument(format!("Invalid header name {name}: {e}")))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| Error::InvalidArgument(format!("Invalid value for header {name}: {e}")))?;
+            headers.append(name, value);
+        }
+
+        Ok(headers)
+    }
+}
@@ -1,202 +1,437 @@
-use serde_json::json;
-use anyhow::{Context, Result};
+use std::path::Path;
+use std::collections::HashMap;
+use serde_json::{json, Value};
+use serde::de::DeserializeOwned;
 
-use crate::tab::Tab;
+use crate::{ImageFormat, Rgba};
 use crate::general_utils;
-use crate::general_utils::next_id;
+use crate::tab::{Clip, Tab};
+use crate::error::{Error, Result};
 
 /// Represents screenshot configuration parameters.
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct ScreenshotConfig {
-    pub format: &'static str,
-    pub quality: Option<u8>,
+    pub format: ImageFormat,
+    /// Background color to render behind the page while capturing,
+    /// e.g. [`Rgba::TRANSPARENT`] to keep the alpha channel.
+    pub background: Option<Rgba>,
+    /// Which CSS box of an element to clip to.
+    pub box_kind: BoxKind,
+    /// Extra space around the element's box, e.g. to include a `box-shadow`.
+    pub padding: Insets,
 }
 
-impl Default for ScreenshotConfig {
-    fn default() -> Self {
-        Self {
-            format: "png",
-            quality: None,
+/// One of the CSS boxes reported by `DOM.getBoxModel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoxKind {
+    /// The content box.
+    Content,
+    /// The content plus padding.
+    Padding,
+    /// The content, padding and border.
+    #[default]
+    Border,
+    /// The content, padding, border and margin.
+    Margin,
+}
+
+impl BoxKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BoxKind::Content => "content",
+            BoxKind::Padding => "padding",
+            BoxKind::Border => "border",
+            BoxKind::Margin => "margin",
         }
     }
 }
 
+/// Distances from each side of a rectangle, in CSS pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Insets {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Insets {
+    /// Create insets with a different value for each side.
+    pub fn new(top: f64, right: f64, bottom: f64, left: f64) -> Self {
+        Self { top, right, bottom, left }
+    }
+
+    /// Create insets with the same value on all four sides.
+    pub fn uniform(value: f64) -> Self {
+        Self::new(value, value, value, value)
+    }
+}
+
+/// The position and size of an element's border box, in CSS pixels
+/// relative to the top left of the document.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
 /// An element instance.
 pub struct Element<'a> {
     parent: &'a Tab,
-    // node_id: u64,
-    // value: String,
-    // tag_name: String,
+    tag_name: String,
     backend_node_id: u64,
-    // attributes: Vec<String>,
-    // remote_object_id: String,
 }
 
 impl<'a> Element<'a> {
     pub(crate) async fn new(parent: &'a Tab, node_id: u64) -> Result<Self> {
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "DOM.describeNode",
-            "params": {
-                "nodeId": node_id,
-                "depth": 100
-            }
-        }).to_string();
+        let res = parent.send_cmd("DOM.describeNode", json!({
+            "nodeId": node_id,
+            "depth": 100
+        })).await?;
 
-        let res = general_utils::send_and_get_msg(parent.transport.clone(), msg_id, &parent.session_id, msg).await?;
-
-        let msg = general_utils::serde_msg(&res);
-
-        let node = msg["result"]
+        let node = res
             .get("node")
-            .context("Failed to get node")?;
-
-        // let attributes = node
-        //     .get("attributes")
-        //     .context("Failed to get attributes")?
-        //     .to_string();
+            .ok_or_else(|| Error::protocol("Failed to get node"))?;
 
-        // let attributes: Vec<String> = serde_json::from_str(&attributes)?;
-
-        // let tag_name = node
-        //     .get("nodeName")
-        //     .context("Failed to get nodeName")?
-        //     .as_str()
-        //     .context("Failed to convert nodeName to string")?
-        //     .to_string();
+        let tag_name = node
+            .get("localName")
+            .and_then(|name| name.as_str())
+            .filter(|name| !name.is_empty())
+            .or_else(|| node.get("nodeName").and_then(|name| name.as_str()))
+            .ok_or_else(|| Error::protocol("Failed to get nodeName"))?
+            .to_lowercase();
 
         let backend_node_id = node
             .get("backendNodeId")
-            .context("Failed to get backendNodeId")?
-            .as_u64()
-            .context("Failed to convert backendNodeId to u64")?;
-
-        // let msg_id = next_id();
-        // let msg = json!({
-        //     "id": msg_id,
-        //     "method": "DOM.resolveNode",
-        //     "params": {
-        //         "backendNodeId": backend_node_id,
-        //     }
-        // }).to_string();
-
-        // let res = general_utils::send_and_get_msg(parent.transport.clone(), msg_id, &parent.session_id, msg).await?;
-
-        // let msg = general_utils::serde_msg(&res);
-        // let object = msg["result"]
-        //     .get("object")
-        //     .context("Failed to get an object")?;
-
-        // let value = object
-        //     .get("value")
-        //     .unwrap_or(&json!(""))
-        //     .to_string();
-
-        // let remote_object_id = object
-        //     .get("objectId")
-        //     .context("Failed to get objectId")?
-        //     .as_str()
-        //     .context("Failed to convert objectId to string")?
-        //     .to_string();
+            .and_then(|id| id.as_u64())
+            .ok_or_else(|| Error::protocol("Failed to get backendNodeId"))?;
 
         Ok(Self {
             parent,
-            // value,
-            // node_id,
-            // tag_name,
-            // attributes,
+            tag_name,
             backend_node_id,
-            // remote_object_id,
         })
     }
 
-    /// Get the box model dimensions for an element.
-    async fn get_box_model_dimensions(&self) -> Result<(f64, f64, f64, f64)> {
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "DOM.getBoxModel",
-            "params": {
-                "backendNodeId": self.backend_node_id
-            }
-        }).to_string();
-
-        let res = general_utils::send_and_get_msg(
-            self.parent.transport.clone(),
-            msg_id,
-            &self.parent.session_id,
-            msg
-        ).await?;
-
-        let msg = general_utils::serde_msg(&res);
-        let model = msg["result"]
+    /// Get the lowercase tag name of the element, e.g. `div`.
+    pub fn tag_name(&self) -> &str {
+        &self.tag_name
+    }
+
+    /**
+    Get the current attributes of the element.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content(r#"<a href="/docs" class="link">Docs</a>"#).await?;
+
+        let element = tab.find_element("a").await?;
+        let attributes = element.attributes().await?;
+        assert_eq!(attributes["href"], "/docs");
+        Ok(())
+    }
+    ```
+    */
+    pub async fn attributes(&self) -> Result<HashMap<String, String>> {
+        let res = self.parent.send_cmd("DOM.describeNode", json!({
+            "backendNodeId": self.backend_node_id
+        })).await?;
+
+        // Attributes are a flat list of alternating names and values.
+        let attributes = res["node"]["attributes"]
+            .as_array()
+            .map(|list| {
+                list.chunks_exact(2)
+                    .filter_map(|pair| Some((
+                        pair[0].as_str()?.to_string(),
+                        pair[1].as_str()?.to_string(),
+                    )))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(attributes)
+    }
+
+    /// Get the rendered text of the element (`innerText`).
+    pub async fn text(&self) -> Result<String> {
+        self.call_on("function() { return this.innerText ?? this.textContent ?? ''; }").await
+    }
+
+    /// Get the HTML markup of the element's contents.
+    pub async fn inner_html(&self) -> Result<String> {
+        self.call_on("function() { return this.innerHTML ?? ''; }").await
+    }
+
+    /// Get the HTML markup of the element, including the element itself.
+    pub async fn outer_html(&self) -> Result<String> {
+        let res = self.parent.send_cmd("DOM.getOuterHTML", json!({
+            "backendNodeId": self.backend_node_id
+        })).await?;
+
+        res["outerHTML"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::protocol("Failed to get outerHTML"))
+    }
+
+    /**
+    Get the position and size of the element's border box.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content(r#"<div style="width: 400px; height: 300px"></div>"#).await?;
+
+        let element = tab.find_element("div").await?;
+        let rect = element.bounding_box().await?;
+        println!("{}x{} at ({}, {})", rect.width, rect.height, rect.x, rect.y);
+        Ok(())
+    }
+    ```
+    */
+    pub async fn bounding_box(&self) -> Result<BoundingBox> {
+        let clip = self.get_document_box(BoxKind::Border).await?;
+
+        Ok(BoundingBox {
+            x: clip.x,
+            y: clip.y,
+            width: clip.width,
+            height: clip.height,
+        })
+    }
+
+    /// Scroll the element into view if it is not already visible.
+    pub async fn scroll_into_view(&self) -> Result<&Self> {
+        self.parent.send_cmd("DOM.scrollIntoViewIfNeeded", json!({
+            "backendNodeId": self.backend_node_id
+        })).await?;
+
+        Ok(self)
+    }
+
+    /// Focus the element.
+    pub async fn focus(&self) -> Result<&Self> {
+        self.parent.send_cmd("DOM.focus", json!({
+            "backendNodeId": self.backend_node_id
+        })).await?;
+
+        Ok(self)
+    }
+
+    /**
+    Scroll the element into view and click the centre of its border box.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content(r#"
+            <details><summary>Menu</summary><ul><li>One</li><li>Two</li></ul></details>
+        "#).await?;
+
+        tab.find_element("summary").await?.click().await?;
+        tab.find_element("details").await?.save_to("menu.png").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn click(&self) -> Result<&Self> {
+        let (x, y) = self.centre().await?;
+        self.parent.mouse().click(x, y).await?;
+
+        Ok(self)
+    }
+
+    /// Scroll the element into view and move the mouse over its centre.
+    pub async fn hover(&self) -> Result<&Self> {
+        let (x, y) = self.centre().await?;
+        self.parent.mouse().move_to(x, y).await?;
+
+        Ok(self)
+    }
+
+    /**
+    Focus the element and type text into it.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content(r#"<input placeholder="Name">"#).await?;
+
+        let input = tab.find_element("input").await?;
+        input.type_text("Ferris").await?;
+        input.save_to("input.png").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn type_text(&self, text: &str) -> Result<&Self> {
+        self.focus().await?;
+        self.parent.keyboard().type_text(text).await?;
+
+        Ok(self)
+    }
+
+    /// Focus the element and press a key, e.g. `Enter` or `ArrowDown`.
+    pub async fn press_key(&self, key: &str) -> Result<&Self> {
+        self.focus().await?;
+        self.parent.keyboard().press(key).await?;
+
+        Ok(self)
+    }
+
+    /// Scroll the element into view and get the viewport coordinates of its centre.
+    async fn centre(&self) -> Result<(f64, f64)> {
+        self.scroll_into_view().await?;
+        let clip = self.get_box_model_dimensions(BoxKind::Border).await?;
+
+        Ok((clip.x + clip.width / 2.0, clip.y + clip.height / 2.0))
+    }
+
+    /// Call a JavaScript function with the element as `this` and deserialize its result.
+    async fn call_on<T: DeserializeOwned>(&self, function: &str) -> Result<T> {
+        let res = self.parent.send_cmd("DOM.resolveNode", json!({
+            "backendNodeId": self.backend_node_id
+        })).await?;
+        let object_id = res["object"]["objectId"]
+            .as_str()
+            .ok_or_else(|| Error::protocol("Failed to get objectId"))?;
+
+        let res = self.parent.send_cmd("Runtime.callFunctionOn", json!({
+            "objectId": object_id,
+            "functionDeclaration": function,
+            "awaitPromise": true,
+            "returnByValue": true
+        })).await;
+
+        self.parent.send_cmd("Runtime.releaseObject", json!({
+            "objectId": object_id
+        })).await?;
+
+        let res = res?;
+        if let Some(details) = res.get("exceptionDetails") {
+            return Err(general_utils::js_exception(details));
+        }
+
+        let value = res["result"].get("value").cloned().unwrap_or(Value::Null);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Get the bounding rectangle of one of the element's boxes, in document coordinates.
+    ///
+    /// Screenshot clips are in document coordinates, while box model quads are relative to
+    /// the viewport and shift whenever the page scrolls, e.g. after [`click`](Self::click).
+    async fn get_document_box(&self, kind: BoxKind) -> Result<Clip> {
+        let rect = self.get_box_model_dimensions(kind).await?;
+        let (page_x, page_y) = self.parent.scroll_offset().await?;
+
+        Ok(Clip {
+            x: rect.x + page_x,
+            y: rect.y + page_y,
+            ..rect
+        })
+    }
+
+    /// Get the bounding rectangle of one of the element's boxes.
+    async fn get_box_model_dimensions(&self, kind: BoxKind) -> Result<Clip> {
+        let res = self.parent.send_cmd("DOM.getBoxModel", json!({
+            "backendNodeId": self.backend_node_id
+        })).await?;
+
+        let model = res
             .get("model")
-            .context("Failed to get model")?;
+            .ok_or_else(|| Error::protocol("Failed to get model"))?;
+
+        // A quad is four points, clockwise from the top left: [x1, y1, ..., x4, y4].
+        let quad = model[kind.as_str()]
+            .as_array()
+            .filter(|quad| quad.len() == 8)
+            .ok_or_else(|| Error::protocol(format!("Failed to get {} quad", kind.as_str())))?;
+        let quad: Vec<f64> = quad.iter().filter_map(|v| v.as_f64()).collect();
+
+        let xs = quad.iter().step_by(2);
+        let ys = quad.iter().skip(1).step_by(2);
+        let (min_x, max_x) = xs.fold((f64::MAX, f64::MIN), |(lo, hi), &x| (lo.min(x), hi.max(x)));
+        let (min_y, max_y) = ys.fold((f64::MAX, f64::MIN), |(lo, hi), &y| (lo.min(y), hi.max(y)));
 
-        Ok((
-            model["border"][0].as_f64().unwrap(), // top_left_x
-            model["border"][1].as_f64().unwrap(), // top_left_y
-            model["border"][2].as_f64().unwrap(), // top_right_x
-            model["border"][5].as_f64().unwrap()  // bottom_left_y
-        ))
+        Ok(Clip {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        })
     }
 
-    /// Take a screenshot with the given configuration.
+    /**
+    Take a screenshot with the given configuration.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, BoxKind, Insets, ScreenshotConfig};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content(r#"<div class="card" style="box-shadow: 0 4px 16px #0004">Hi</div>"#).await?;
+
+        let element = tab.find_element(".card").await?;
+        let base64 = element.take_screenshot_with_config(ScreenshotConfig {
+            box_kind: BoxKind::Margin,
+            padding: Insets::uniform(20.0),
+            ..Default::default()
+        }).await?;
+        Ok(())
+    }
+    ```
+    */
     pub async fn take_screenshot_with_config(&self, config: ScreenshotConfig) -> Result<String> {
-        let (top_left_x, top_left_y, top_right_x, bottom_left_y) =
-            self.get_box_model_dimensions().await?;
-
-        let mut params = json!({
-            "format": config.format,
-            "clip": {
-                "x": top_left_x,
-                "y": top_left_y,
-                "width": top_right_x - top_left_x,
-                "height": bottom_left_y - top_left_y,
-                "scale": 1.0
-            },
-            "fromSurface": true,
-            "captureBeyondViewport": true,
-        });
-
-        if config.format == "jpeg" {
-            if let Some(quality) = config.quality {
-                params["quality"] = json!(quality);
-            }
-        }
+        let rect = self.get_document_box(config.box_kind).await?;
+        let padding = config.padding;
 
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "Page.captureScreenshot",
-            "params": params
-        }).to_string();
-
-        self.parent.activate().await?;
-        let res = general_utils::send_and_get_msg(
-            self.parent.transport.clone(),
-            msg_id,
-            &self.parent.session_id,
-            msg
-        ).await?;
-
-        let msg = general_utils::serde_msg(&res);
-        let base64 = msg["result"]
-            .get("data")
-            .context("Failed to get data")?
-            .as_str()
-            .context("Failed to convert data to string")?
-            .to_string();
+        let clip = Clip {
+            x: rect.x - padding.left,
+            y: rect.y - padding.top,
+            width: rect.width + padding.left + padding.right,
+            height: rect.height + padding.top + padding.bottom,
+        };
 
-        Ok(base64)
+        self.parent.capture(&config, clip).await
     }
 
     /// Capture a screenshot of the element in JPEG format.
     pub async fn screenshot(&self) -> Result<String> {
         self.take_screenshot_with_config(ScreenshotConfig {
-            format: "jpeg",
-            quality: Some(90),
+            format: ImageFormat::Jpeg { quality: 90 },
+            ..Default::default()
         }).await
     }
 
@@ -204,4 +439,70 @@ impl<'a> Element<'a> {
     pub async fn raw_screenshot(&self) -> Result<String> {
         self.take_screenshot_with_config(ScreenshotConfig::default()).await
     }
+
+    /**
+    Take a screenshot with the given configuration and return the decoded image bytes.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, ScreenshotConfig};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content("<h1>Hello world!</h1>").await?;
+
+        let element = tab.find_element("h1").await?;
+        let png = element.screenshot_bytes(ScreenshotConfig::default()).await?;
+        std::fs::write("h1.png", png)?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn screenshot_bytes(&self, config: ScreenshotConfig) -> Result<Vec<u8>> {
+        let base64 = self.take_screenshot_with_config(config).await?;
+        general_utils::decode_base64(&base64)
+    }
+
+    /// Take a screenshot with the given configuration and decode it into an image.
+    #[cfg(feature = "image")]
+    pub async fn screenshot_image(&self, config: ScreenshotConfig) -> Result<image::DynamicImage> {
+        let bytes = self.screenshot_bytes(config).await?;
+        general_utils::decode_image(&bytes)
+    }
+
+    /**
+    Take a screenshot and save it to `path`.
+
+    The image format is chosen from the file extension (`png`, `jpg`/`jpeg` or `webp`).
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content("<h1>Hello world!</h1>").await?;
+        tab.find_element("h1").await?.save_to("h1.webp").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn save_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = general_utils::format_from_path(path)?;
+
+        let bytes = self.screenshot_bytes(ScreenshotConfig {
+            format,
+            ..Default::default()
+        }).await?;
+
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
 }
\ No newline at end of file
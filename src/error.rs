@@ -0,0 +1,122 @@
+use std::fmt;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite;
+
+use crate::ElementState;
+
+/// A specialized `Result` type for cdp-html-shot operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The error type for all browser, tab and element operations.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// No element matched the given selector.
+    ElementNotFound { selector: String },
+    /// The browser did not answer a CDP command in time.
+    Timeout { method: String },
+    /// A capture did not finish within its deadline.
+    CaptureTimeout { timeout: Duration },
+    /// An element did not reach the awaited state in time.
+    WaitTimeout {
+        selector: String,
+        state: ElementState,
+        waited: Duration,
+        last_state: ElementState,
+    },
+    /// The page did not become ready in time.
+    ReadyTimeout { waited: Duration, pending: String },
+    /// The browser could not navigate to a URL.
+    Navigation { url: String, error: String },
+    /// The browser answered a CDP command with an error.
+    Cdp { code: i64, message: String, data: Option<String> },
+    /// The browser process could not be found or started.
+    BrowserLaunch(String),
+    /// The connection to the browser was closed.
+    ConnectionClosed,
+    /// A script threw an exception in the page.
+    JavaScript { message: String, stack: Option<String> },
+    /// The given options cannot be used together.
+    InvalidArgument(String),
+    /// The browser sent a reply that does not have the expected shape.
+    Protocol(String),
+    /// An I/O error occurred.
+    Io(std::io::Error),
+    /// A WebSocket error occurred.
+    WebSocket(Box<tungstenite::Error>),
+    /// A JSON (de)serialization error occurred.
+    Json(serde_json::Error),
+    /// A screenshot could not be decoded.
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
+}
+
+impl Error {
+    pub(crate) fn protocol(msg: impl Into<String>) -> Self {
+        Error::Protocol(msg.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ElementNotFound { selector } => write!(f, "Element not found: {selector}"),
+            Error::Timeout { method } => write!(f, "Timeout while waiting for response to {method}"),
+            Error::CaptureTimeout { timeout } => write!(f, "Capture did not finish within {timeout:?}"),
+            Error::WaitTimeout { selector, state, waited, last_state } => write!(
+                f,
+                "Timed out after {waited:?} waiting for {selector} to be {state} (last seen: {last_state})"
+            ),
+            Error::ReadyTimeout { waited, pending } => {
+                write!(f, "Timed out after {waited:?} waiting for the page to be ready (pending: {pending})")
+            }
+            Error::Navigation { url, error } => write!(f, "Failed to navigate to {url}: {error}"),
+            Error::Cdp { code, message, data: Some(data) } => write!(f, "CDP error {code}: {message} ({data})"),
+            Error::Cdp { code, message, data: None } => write!(f, "CDP error {code}: {message}"),
+            Error::BrowserLaunch(msg) => write!(f, "Failed to launch browser: {msg}"),
+            Error::ConnectionClosed => write!(f, "Connection closed"),
+            Error::JavaScript { message, stack: Some(stack) } => {
+                write!(f, "JavaScript error: {message}\n{stack}")
+            }
+            Error::JavaScript { message, stack: None } => write!(f, "JavaScript error: {message}"),
+            Error::InvalidArgument(msg) => write!(f, "Invalid argument: {msg}"),
+            Error::Protocol(msg) => write!(f, "Unexpected CDP response: {msg}"),
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::WebSocket(e) => write!(f, "WebSocket error: {e}"),
+            Error::Json(e) => write!(f, "JSON error: {e}"),
+            #[cfg(feature = "image")]
+            Error::Image(e) => write!(f, "Image error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::WebSocket(e) => Some(e.as_ref()),
+            Error::Json(e) => Some(e),
+            #[cfg(feature = "image")]
+            Error::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<tungstenite::Error> for Error {
+    fn from(e: tungstenite::Error) -> Self {
+        Error::WebSocket(Box::new(e))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
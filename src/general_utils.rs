@@ -1,10 +1,12 @@
-use std::sync::Arc;
-use anyhow::{anyhow, Result};
-use serde_json::{json, Value};
+use std::path::Path;
+use std::future::Future;
+use std::time::Duration;
+use base64::Engine;
+use serde_json::Value;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::transport::Transport;
-use crate::transport_actor::{TargetMessage, TransportResponse};
+use crate::ImageFormat;
+use crate::error::{Error, Result};
 
 pub(crate) static GLOBAL_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
@@ -12,31 +14,191 @@ pub(crate) fn next_id() -> usize {
     GLOBAL_ID_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
 }
 
-pub(crate) fn serde_msg(msg: &TargetMessage) -> Value {
-    let message: Value = serde_json::from_str(msg.params["message"].as_str().unwrap().trim_matches('"')).unwrap();
-    message
-}
-
-pub(crate) async fn send_and_get_msg(
-    transport: Arc<Transport>,
-    msg_id: usize,
-    session_id: &str,
-    msg: String,
-) -> Result<TargetMessage> {
-    let (_, target_msg) = futures::try_join!(
-        transport.send(json!({
-            "id": next_id(),
-            "method": "Target.sendMessageToTarget",
-            "params": {
-                "sessionId": session_id,
-                "message": msg
-            }
-        })),
-        transport.get_target_msg(msg_id),
-    )?;
+pub(crate) fn decode_base64(data: &str) -> Result<Vec<u8>> {
+    base64::prelude::BASE64_STANDARD
+        .decode(data)
+        .map_err(|e| Error::protocol(format!("Invalid base64 data: {e}")))
+}
+
+#[cfg(feature = "image")]
+pub(crate) fn decode_image(data: &[u8]) -> Result<image::DynamicImage> {
+    image::load_from_memory(data).map_err(Error::Image)
+}
+
+/// Take the `result` of a command reply, turning an error reply into [`Error::Cdp`].
+pub(crate) fn reply_result(mut reply: Value) -> Result<Value> {
+    let Some(error) = reply.get("error") else {
+        return Ok(reply["result"].take());
+    };
+
+    // `data` is usually a string, but nothing in the protocol guarantees it.
+    let data = match &error["data"] {
+        Value::Null => None,
+        Value::String(data) => Some(data.clone()),
+        data => Some(data.to_string()),
+    };
+
+    Err(Error::Cdp {
+        code: error["code"].as_i64().unwrap_or_default(),
+        message: error["message"].as_str().unwrap_or_default().to_string(),
+        data,
+    })
+}
+
+/// Turn the `exceptionDetails` of a `Runtime.evaluate` reply into [`Error::JavaScript`].
+pub(crate) fn js_exception(details: &Value) -> Error {
+    let description = details["exception"]["description"]
+        .as_str()
+        .or_else(|| details["exception"]["value"].as_str())
+        .or_else(|| details["text"].as_str())
+        .unwrap_or("Uncaught exception");
+
+    // Error objects describe themselves as "Error: message\n    at frame...".
+    let (message, stack) = match description.split_once('\n') {
+        Some((message, stack)) => (message, Some(stack.to_string())),
+        None => (description, None),
+    };
+
+    let stack = stack.or_else(|| {
+        let frames = details["stackTrace"]["callFrames"].as_array()?;
+        let lines: Vec<String> = frames
+            .iter()
+            .map(|frame| format!(
+                "    at {} ({}:{}:{})",
+                frame["functionName"].as_str().filter(|f| !f.is_empty()).unwrap_or("<anonymous>"),
+                frame["url"].as_str().unwrap_or_default(),
+                frame["lineNumber"].as_u64().unwrap_or_default() + 1,
+                frame["columnNumber"].as_u64().unwrap_or_default() + 1,
+            ))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    });
 
-    match target_msg {
-        TransportResponse::Target(res) => Ok(res),
-        other => Err(anyhow!("Unexpected transport response: {:?}", other)),
+    Error::JavaScript {
+        message: message.to_string(),
+        stack,
     }
-}
\ No newline at end of file
+}
+
+/// Run `fut`, failing with [`Error::CaptureTimeout`] once `timeout` has passed.
+pub(crate) async fn with_deadline<T>(timeout: Option<Duration>, fut: impl Future<Output = Result<T>>) -> Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, fut)
+            .await
+            .map_err(|_| Error::CaptureTimeout { timeout })?,
+        None => fut.await,
+    }
+}
+
+pub(crate) fn format_from_path(path: &Path) -> Result<ImageFormat> {
+    ImageFormat::from_path(path).ok_or_else(|| {
+        Error::InvalidArgument(format!("Unsupported image extension: {}", path.display()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reply_result_takes_result() {
+        let reply = json!({ "id": 1, "result": { "value": 42 } });
+        assert_eq!(reply_result(reply).unwrap(), json!({ "value": 42 }));
+    }
+
+    #[test]
+    fn reply_result_without_result_is_null() {
+        assert_eq!(reply_result(json!({ "id": 1 })).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn reply_result_turns_error_into_cdp_error() {
+        let reply = json!({
+            "id": 1,
+            "error": { "code": -32000, "message": "No node found", "data": "details" }
+        });
+        let Err(Error::Cdp { code, message, data }) = reply_result(reply) else {
+            panic!("expected a CDP error");
+        };
+        assert_eq!(code, -32000);
+        assert_eq!(message, "No node found");
+        assert_eq!(data.as_deref(), Some("details"));
+    }
+
+    #[test]
+    fn reply_result_serializes_non_string_error_data() {
+        let reply = json!({
+            "id": 1,
+            "error": { "code": -32602, "message": "Invalid params", "data": { "param": "url" } }
+        });
+        let Err(Error::Cdp { data, .. }) = reply_result(reply) else {
+            panic!("expected a CDP error");
+        };
+        assert_eq!(data.as_deref(), Some(r#"{"param":"url"}"#));
+    }
+
+    #[test]
+    fn reply_result_without_error_data() {
+        let reply = json!({ "id": 1, "error": { "code": -32601, "message": "Method not found" } });
+        let Err(Error::Cdp { data, .. }) = reply_result(reply) else {
+            panic!("expected a CDP error");
+        };
+        assert_eq!(data, None);
+    }
+
+    fn message_and_stack(details: Value) -> (String, Option<String>) {
+        match js_exception(&details) {
+            Error::JavaScript { message, stack } => (message, stack),
+            error => panic!("expected a JavaScript error, got {error:?}"),
+        }
+    }
+
+    #[test]
+    fn js_exception_splits_description_into_message_and_stack() {
+        let (message, stack) = message_and_stack(json!({
+            "text": "Uncaught",
+            "exception": { "description": "Error: boom\n    at f (page.js:1:7)" }
+        }));
+        assert_eq!(message, "Error: boom");
+        assert_eq!(stack.as_deref(), Some("    at f (page.js:1:7)"));
+    }
+
+    #[test]
+    fn js_exception_uses_thrown_value() {
+        let (message, stack) = message_and_stack(json!({
+            "text": "Uncaught",
+            "exception": { "type": "string", "value": "plain string" }
+        }));
+        assert_eq!(message, "plain string");
+        assert_eq!(stack, None);
+    }
+
+    #[test]
+    fn js_exception_falls_back_to_text() {
+        let (message, _) = message_and_stack(json!({ "text": "Uncaught SyntaxError" }));
+        assert_eq!(message, "Uncaught SyntaxError");
+
+        let (message, _) = message_and_stack(json!({}));
+        assert_eq!(message, "Uncaught exception");
+    }
+
+    #[test]
+    fn js_exception_formats_call_frames_as_stack() {
+        let (message, stack) = message_and_stack(json!({
+            "text": "Uncaught",
+            "exception": { "type": "number", "value": 1, "description": "1" },
+            "stackTrace": {
+                "callFrames": [
+                    { "functionName": "inner", "url": "page.js", "lineNumber": 2, "columnNumber": 4 },
+                    { "functionName": "", "url": "page.js", "lineNumber": 9, "columnNumber": 0 }
+                ]
+            }
+        }));
+        assert_eq!(message, "1");
+        assert_eq!(
+            stack.as_deref(),
+            Some("    at inner (page.js:3:5)\n    at <anonymous> (page.js:10:1)")
+        );
+    }
+}
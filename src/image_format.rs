@@ -0,0 +1,57 @@
+use std::path::Path;
+
+/// The image format of a screenshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageFormat {
+    /// Lossless PNG.
+    #[default]
+    Png,
+    /// JPEG with a quality between 0 and 100.
+    Jpeg { quality: u8 },
+    /// WebP with a quality between 0 and 100.
+    Webp { quality: u8 },
+}
+
+impl ImageFormat {
+    /**
+    Guess the format from a file extension.
+
+    Recognizes `png`, `jpg`/`jpeg` and `webp` (case-insensitive);
+    lossy formats use a quality of 90.
+
+    # Example
+    ```
+    use cdp_html_shot::ImageFormat;
+
+    assert_eq!(ImageFormat::from_path("card.webp"), Some(ImageFormat::Webp { quality: 90 }));
+    assert_eq!(ImageFormat::from_path("card.gif"), None);
+    ```
+    */
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg { quality: 90 }),
+            "webp" => Some(ImageFormat::Webp { quality: 90 }),
+            _ => None,
+        }
+    }
+
+    /// The `format` name understood by `Page.captureScreenshot`.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg { .. } => "jpeg",
+            ImageFormat::Webp { .. } => "webp",
+        }
+    }
+
+    /// The compression quality, if the format is lossy.
+    pub(crate) fn quality(&self) -> Option<u8> {
+        match self {
+            ImageFormat::Png => None,
+            ImageFormat::Jpeg { quality } | ImageFormat::Webp { quality } => Some(*quality),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use serde_json::json;
+
+use crate::Tab;
+use crate::error::{Error, Result};
+
+/// A mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MouseButton::Left => "left",
+            MouseButton::Middle => "middle",
+            MouseButton::Right => "right",
+        }
+    }
+}
+
+/**
+Coordinate-based mouse input for a tab, created with [`Tab::mouse`].
+
+Coordinates are in CSS pixels relative to the top left of the viewport.
+
+# Example
+```no_run
+use cdp_html_shot::Browser;
+use anyhow::Result;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let browser = Browser::new().await?;
+    let tab = browser.new_tab().await?;
+    tab.set_content("<button onclick=\"this.textContent = 'Clicked'\">Click</button>").await?;
+
+    tab.mouse().click(20.0, 15.0).await?;
+    Ok(())
+}
+```
+*/
+pub struct Mouse<'a> {
+    tab: &'a Tab,
+}
+
+impl<'a> Mouse<'a> {
+    pub(crate) fn new(tab: &'a Tab) -> Self {
+        Self { tab }
+    }
+
+    /// Move the mouse pointer to the given point.
+    pub async fn move_to(&self, x: f64, y: f64) -> Result<()> {
+        self.dispatch("mouseMoved", x, y, MouseButton::Left, 0).await
+    }
+
+    /// Press a mouse button at the given point.
+    pub async fn down(&self, x: f64, y: f64, button: MouseButton) -> Result<()> {
+        self.dispatch("mousePressed", x, y, button, 1).await
+    }
+
+    /// Release a mouse button at the given point.
+    pub async fn up(&self, x: f64, y: f64, button: MouseButton) -> Result<()> {
+        self.dispatch("mouseReleased", x, y, button, 1).await
+    }
+
+    /// Move to the given point and click it with the left button.
+    pub async fn click(&self, x: f64, y: f64) -> Result<()> {
+        self.move_to(x, y).await?;
+        self.down(x, y, MouseButton::Left).await?;
+        self.up(x, y, MouseButton::Left).await
+    }
+
+    /// Scroll the page under the given point by `delta_x` and `delta_y` pixels.
+    pub async fn wheel(&self, x: f64, y: f64, delta_x: f64, delta_y: f64) -> Result<()> {
+        self.tab.send_cmd("Input.dispatchMouseEvent", json!({
+            "type": "mouseWheel",
+            "x": x,
+            "y": y,
+            "deltaX": delta_x,
+            "deltaY": delta_y
+        })).await?;
+
+        Ok(())
+    }
+
+    async fn dispatch(&self, kind: &str, x: f64, y: f64, button: MouseButton, click_count: u32) -> Result<()> {
+        let button = if kind == "mouseMoved" { "none" } else { button.as_str() };
+
+        self.tab.send_cmd("Input.dispatchMouseEvent", json!({
+            "type": kind,
+            "x": x,
+            "y": y,
+            "button": button,
+            "clickCount": click_count
+        })).await?;
+
+        Ok(())
+    }
+}
+
+/**
+Keyboard input for the focused element of a tab, created with [`Tab::keyboard`].
+
+# Example
+```no_run
+use cdp_html_shot::Browser;
+use anyhow::Result;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let browser = Browser::new().await?;
+    let tab = browser.new_tab().await?;
+    tab.set_content("<input autofocus>").await?;
+
+    tab.keyboard().type_text("Hello").await?;
+    tab.keyboard().press("Enter").await?;
+    Ok(())
+}
+```
+*/
+pub struct Keyboard<'a> {
+    tab: &'a Tab,
+}
+
+impl<'a> Keyboard<'a> {
+    pub(crate) fn new(tab: &'a Tab) -> Self {
+        Self { tab }
+    }
+
+    /**
+    Press and release a key.
+
+    `key` is either a single character or a DOM key name such as `Enter`,
+    `Tab`, `Backspace`, `Escape` or `ArrowDown`. Other key names fail with
+    [`Error::InvalidArgument`].
+    */
+    pub async fn press(&self, key: &str) -> Result<()> {
+        self.down(key).await?;
+        self.up(key).await
+    }
+
+    /// Press a key without releasing it.
+    pub async fn down(&self, key: &str) -> Result<()> {
+        let def = KeyDefinition::from_key(key)?;
+        let kind = if def.text.is_some() { "keyDown" } else { "rawKeyDown" };
+
+        let mut params = json!({
+            "type": kind,
+            "key": def.key,
+            "code": def.code,
+            "windowsVirtualKeyCode": def.key_code,
+            "nativeVirtualKeyCode": def.key_code
+        });
+        if let Some(text) = def.text {
+            params["text"] = json!(text);
+            params["unmodifiedText"] = json!(text);
+        }
+
+        self.tab.send_cmd("Input.dispatchKeyEvent", params).await?;
+
+        Ok(())
+    }
+
+    /// Release a key pressed with [`down`](Self::down).
+    pub async fn up(&self, key: &str) -> Result<()> {
+        let def = KeyDefinition::from_key(key)?;
+
+        self.tab.send_cmd("Input.dispatchKeyEvent", json!({
+            "type": "keyUp",
+            "key": def.key,
+            "code": def.code,
+            "windowsVirtualKeyCode": def.key_code,
+            "nativeVirtualKeyCode": def.key_code
+        })).await?;
+
+        Ok(())
+    }
+
+    /// Type text character by character, as if each key were pressed.
+    pub async fn type_text(&self, text: &str) -> Result<()> {
+        let mut buf = [0; 4];
+        for c in text.chars() {
+            self.press(c.encode_utf8(&mut buf)).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The fields of `Input.dispatchKeyEvent` for a key.
+struct KeyDefinition {
+    key: String,
+    code: String,
+    key_code: u32,
+    text: Option<String>,
+}
+
+impl KeyDefinition {
+    fn from_key(key: &str) -> Result<Self> {
+        let named = match key {
+            "Enter" => Some(("Enter", 13, Some("\r"))),
+            "Tab" => Some(("Tab", 9, None)),
+            "Backspace" => Some(("Backspace", 8, None)),
+            "Escape" => Some(("Escape", 27, None)),
+            "Delete" => Some(("Delete", 46, None)),
+            "Home" => Some(("Home", 36, None)),
+            "End" => Some(("End", 35, None)),
+            "PageUp" => Some(("PageUp", 33, None)),
+            "PageDown" => Some(("PageDown", 34, None)),
+            "ArrowLeft" => Some(("ArrowLeft", 37, None)),
+            "ArrowUp" => Some(("ArrowUp", 38, None)),
+            "ArrowRight" => Some(("ArrowRight", 39, None)),
+            "ArrowDown" => Some(("ArrowDown", 40, None)),
+            " " => Some(("Space", 32, Some(" "))),
+            _ => None,
+        };
+        if let Some((code, key_code, text)) = named {
+            return Ok(Self {
+                key: key.to_string(),
+                code: code.to_string(),
+                key_code,
+                text: text.map(String::from),
+            });
+        }
+
+        let mut chars = key.chars();
+        let (code, key_code) = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => {
+                let upper = c.to_ascii_uppercase();
+                (format!("Key{upper}"), upper as u32)
+            }
+            (Some(c), None) if c.is_ascii_digit() => (format!("Digit{c}"), c as u32),
+            // Any other single character is typed as text.
+            (Some(_), None) => (String::new(), 0),
+            _ => return Err(Error::InvalidArgument(format!("Unsupported key: {key:?}"))),
+        };
+
+        Ok(Self {
+            key: key.to_string(),
+            code,
+            key_code,
+            text: Some(key.to_string()),
+        })
+    }
+}
@@ -18,9 +18,8 @@ A Rust library for capturing HTML screenshots using CDP.
 ### Capture HTML screenshot
 
 ```rust
-use base64::Engine;
 use anyhow::Result;
-use cdp_html_shot::Browser;
+use cdp_html_shot::{Browser, CaptureOptions};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -34,9 +33,9 @@ async fn main() -> Result<()> {
     "#;
 
     let browser = Browser::new().await?;
-    let base64 = browser.capture_html(HTML, "html").await?;
-
-    let img_data = base64::prelude::BASE64_STANDARD.decode(base64)?;
+    let img_data = browser
+        .capture_html_bytes(HTML, "html", CaptureOptions::new())
+        .await?;
     std::fs::write("test0.jpeg", img_data)?;
 
     Ok(())
@@ -46,7 +45,6 @@ async fn main() -> Result<()> {
 ### Fine control
 
 ```rust
-use base64::Engine;
 use anyhow::Result;
 use cdp_html_shot::Browser;
 
@@ -58,31 +56,58 @@ async fn main() -> Result<()> {
     tab.set_content("<h1>Hello world!</h1>").await?;
 
     let element = tab.find_element("h1").await?;
-    let base64 = element.screenshot().await?;
+    element.save_to("test0.jpeg").await?;
     tab.close().await?;
 
-    let img_data = base64::prelude::BASE64_STANDARD.decode(base64)?;
-    std::fs::write("test0.jpeg", img_data)?;
-
     Ok(())
 }
 ```
 */
 
 mod tab;
+mod rgba;
+mod error;
 mod browser;
+mod cdp_event;
+mod input;
 mod element;
+mod selector;
+mod viewport;
 mod transport;
+mod image_format;
+mod wait_options;
 mod general_utils;
+mod navigate_options;
+mod media_emulation;
+mod pdf_options;
+mod set_content_options;
+mod ready_options;
 mod transport_actor;
 mod capture_options;
+mod capture_report;
+mod connect_options;
 #[cfg(feature = "atexit")]
 mod exit_hook;
 
 pub use tab::Tab;
+pub use rgba::Rgba;
+pub use error::{Error, Result};
+pub use cdp_event::CdpEvent;
 pub use element::Element;
-pub use element::ScreenshotConfig;
-pub use browser::Browser;
+pub use element::{BoundingBox, BoxKind, Insets, ScreenshotConfig};
+pub use image_format::ImageFormat;
+pub use input::{Keyboard, Mouse, MouseButton};
+pub use browser::{Browser, BrowserBuilder};
+pub use connect_options::ConnectOptions;
+pub use selector::Selector;
+pub use viewport::Viewport;
 pub use capture_options::CaptureOptions;
+pub use capture_report::{CaptureReport, ConsoleMessage, MessageSource};
+pub use pdf_options::PdfOptions;
+pub use set_content_options::SetContentOptions;
+pub use ready_options::ReadyOptions;
+pub use navigate_options::{NavigateOptions, WaitUntil};
+pub use media_emulation::{ColorScheme, Contrast, MediaEmulation, MediaType};
+pub use wait_options::{ElementState, WaitOptions};
 #[cfg(feature = "atexit")]
 pub use exit_hook::ExitHook;
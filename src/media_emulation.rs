@@ -0,0 +1,136 @@
+use serde_json::{json, Value};
+
+/// The CSS media type to emulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    /// `@media screen`.
+    Screen,
+    /// `@media print`.
+    Print,
+}
+
+/// The value of the `prefers-color-scheme` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+/// The value of the `prefers-contrast` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contrast {
+    NoPreference,
+    More,
+    Less,
+    Custom,
+}
+
+/// CSS media type and media features to emulate.
+///
+/// Fields left as `None` keep the browser's own value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MediaEmulation {
+    /// The media type, e.g. [`MediaType::Print`] to apply `@media print` styles.
+    pub media_type: Option<MediaType>,
+    /// The `prefers-color-scheme` media feature.
+    pub color_scheme: Option<ColorScheme>,
+    /// The `prefers-reduced-motion` media feature: `reduce` or `no-preference`.
+    pub reduced_motion: Option<bool>,
+    /// The `forced-colors` media feature: `active` or `none`.
+    pub forced_colors: Option<bool>,
+    /// The `prefers-contrast` media feature.
+    pub contrast: Option<Contrast>,
+}
+
+impl MediaEmulation {
+    /// Create a media emulation that keeps every browser default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Emulate `prefers-color-scheme: dark`.
+    pub fn dark() -> Self {
+        Self::new().with_color_scheme(ColorScheme::Dark)
+    }
+
+    /// Emulate `prefers-color-scheme: light`.
+    pub fn light() -> Self {
+        Self::new().with_color_scheme(ColorScheme::Light)
+    }
+
+    /// Emulate the `print` media type.
+    pub fn print() -> Self {
+        Self::new().with_media_type(MediaType::Print)
+    }
+
+    /// Set the media type.
+    pub fn with_media_type(mut self, media_type: MediaType) -> Self {
+        self.media_type = Some(media_type);
+        self
+    }
+
+    /// Set the `prefers-color-scheme` media feature.
+    pub fn with_color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.color_scheme = Some(color_scheme);
+        self
+    }
+
+    /// Set whether to emulate `prefers-reduced-motion: reduce`.
+    pub fn with_reduced_motion(mut self, reduced: bool) -> Self {
+        self.reduced_motion = Some(reduced);
+        self
+    }
+
+    /// Set whether to emulate `forced-colors: active`.
+    pub fn with_forced_colors(mut self, forced: bool) -> Self {
+        self.forced_colors = Some(forced);
+        self
+    }
+
+    /// Set the `prefers-contrast` media feature.
+    pub fn with_contrast(mut self, contrast: Contrast) -> Self {
+        self.contrast = Some(contrast);
+        self
+    }
+
+    /// The parameters for `Emulation.setEmulatedMedia`; an empty value restores the default.
+    pub(crate) fn to_params(self) -> Value {
+        let media = match self.media_type {
+            Some(MediaType::Screen) => "screen",
+            Some(MediaType::Print) => "print",
+            None => "",
+        };
+        let color_scheme = match self.color_scheme {
+            Some(ColorScheme::Light) => "light",
+            Some(ColorScheme::Dark) => "dark",
+            None => "",
+        };
+        let reduced_motion = match self.reduced_motion {
+            Some(true) => "reduce",
+            Some(false) => "no-preference",
+            None => "",
+        };
+        let forced_colors = match self.forced_colors {
+            Some(true) => "active",
+            Some(false) => "none",
+            None => "",
+        };
+        let contrast = match self.contrast {
+            Some(Contrast::NoPreference) => "no-preference",
+            Some(Contrast::More) => "more",
+            Some(Contrast::Less) => "less",
+            Some(Contrast::Custom) => "custom",
+            None => "",
+        };
+
+        json!({
+            "media": media,
+            "features": [
+                { "name": "prefers-color-scheme", "value": color_scheme },
+                { "name": "prefers-reduced-motion", "value": reduced_motion },
+                { "name": "forced-colors", "value": forced_colors },
+                { "name": "prefers-contrast", "value": contrast }
+            ]
+        })
+    }
+}
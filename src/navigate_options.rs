@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+/// The page lifecycle event that [`Tab::goto`](crate::Tab::goto) waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaitUntil {
+    /// The `load` event: the document and all its resources have loaded.
+    #[default]
+    Load,
+    /// The `DOMContentLoaded` event: the document has been parsed.
+    DomContentLoaded,
+    /// The `load` event, followed by at most `max_inflight` network requests
+    /// for at least `idle_ms` milliseconds.
+    NetworkIdle { idle_ms: u64, max_inflight: usize },
+    /// Do not wait; return as soon as the navigation has started.
+    None,
+}
+
+/// Configuration options for navigation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NavigateOptions {
+    /// The lifecycle event to wait for.
+    pub wait_until: WaitUntil,
+    /// How long to wait before giving up.
+    pub timeout: Duration,
+}
+
+impl Default for NavigateOptions {
+    fn default() -> Self {
+        Self {
+            wait_until: WaitUntil::Load,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl NavigateOptions {
+    /// Create new navigation options with default values (wait for `load`, 30 s timeout).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the lifecycle event to wait for.
+    pub fn with_wait_until(mut self, wait_until: WaitUntil) -> Self {
+        self.wait_until = wait_until;
+        self
+    }
+
+    /// Set how long to wait before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
@@ -0,0 +1,127 @@
+use serde_json::{json, Value};
+
+/// Configuration options for PDF rendering.
+///
+/// Sizes are in inches, as expected by `Page.printToPDF`.
+#[derive(Debug, Clone, Default)]
+pub struct PdfOptions {
+    pub(crate) landscape: bool,
+    pub(crate) print_background: bool,
+    pub(crate) scale: Option<f64>,
+    pub(crate) paper_size: Option<(f64, f64)>,
+    pub(crate) margins: Option<[f64; 4]>,
+    pub(crate) page_ranges: Option<String>,
+    pub(crate) header_template: Option<String>,
+    pub(crate) footer_template: Option<String>,
+    pub(crate) prefer_css_page_size: bool,
+}
+
+impl PdfOptions {
+    /// Create new PDF options with default values (US Letter, portrait).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to print in landscape orientation.
+    pub fn with_landscape(mut self, landscape: bool) -> Self {
+        self.landscape = landscape;
+        self
+    }
+
+    /// Set whether to print background graphics.
+    pub fn with_print_background(mut self, print_background: bool) -> Self {
+        self.print_background = print_background;
+        self
+    }
+
+    /// Set the scale of the webpage rendering, between 0.1 and 2.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Set the paper size in inches, e.g. `(8.27, 11.69)` for A4.
+    pub fn with_paper_size(mut self, width: f64, height: f64) -> Self {
+        self.paper_size = Some((width, height));
+        self
+    }
+
+    /// Set the same margin in inches on all four sides.
+    pub fn with_margin(self, margin: f64) -> Self {
+        self.with_margins(margin, margin, margin, margin)
+    }
+
+    /// Set the top, right, bottom and left margins in inches.
+    pub fn with_margins(mut self, top: f64, right: f64, bottom: f64, left: f64) -> Self {
+        self.margins = Some([top, right, bottom, left]);
+        self
+    }
+
+    /// Set the pages to print, e.g. `"1-5, 8, 11-13"`.
+    pub fn with_page_ranges(mut self, ranges: impl Into<String>) -> Self {
+        self.page_ranges = Some(ranges.into());
+        self
+    }
+
+    /// Set the HTML template for the print header.
+    ///
+    /// The template may use the `date`, `title`, `url`, `pageNumber` and `totalPages`
+    /// classes to inject printing values, e.g. `<span class="pageNumber"></span>`.
+    pub fn with_header_template(mut self, template: impl Into<String>) -> Self {
+        self.header_template = Some(template.into());
+        self
+    }
+
+    /// Set the HTML template for the print footer.
+    ///
+    /// Supports the same classes as [`with_header_template`](Self::with_header_template).
+    pub fn with_footer_template(mut self, template: impl Into<String>) -> Self {
+        self.footer_template = Some(template.into());
+        self
+    }
+
+    /// Set whether to prefer the page size defined by CSS `@page` over the paper size.
+    pub fn with_prefer_css_page_size(mut self, prefer: bool) -> Self {
+        self.prefer_css_page_size = prefer;
+        self
+    }
+
+    /// The `Page.printToPDF` parameters these options resolve to.
+    pub(crate) fn to_params(&self) -> Value {
+        let mut params = json!({
+            "landscape": self.landscape,
+            "printBackground": self.print_background,
+            "preferCSSPageSize": self.prefer_css_page_size,
+            "transferMode": "ReturnAsStream",
+        });
+
+        if let Some(scale) = self.scale {
+            params["scale"] = json!(scale);
+        }
+
+        if let Some((width, height)) = self.paper_size {
+            params["paperWidth"] = json!(width);
+            params["paperHeight"] = json!(height);
+        }
+
+        if let Some([top, right, bottom, left]) = self.margins {
+            params["marginTop"] = json!(top);
+            params["marginRight"] = json!(right);
+            params["marginBottom"] = json!(bottom);
+            params["marginLeft"] = json!(left);
+        }
+
+        if let Some(ranges) = &self.page_ranges {
+            params["pageRanges"] = json!(ranges);
+        }
+
+        if self.header_template.is_some() || self.footer_template.is_some() {
+            params["displayHeaderFooter"] = json!(true);
+            // An empty template still prints Chrome's default header/footer.
+            params["headerTemplate"] = json!(self.header_template.as_deref().unwrap_or("<span></span>"));
+            params["footerTemplate"] = json!(self.footer_template.as_deref().unwrap_or("<span></span>"));
+        }
+
+        params
+    }
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+/// The conditions [`Tab::set_content`](crate::Tab::set_content) waits for before returning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadyOptions {
+    /// Wait for web fonts to load (`document.fonts.ready`).
+    pub wait_fonts: bool,
+    /// Wait for images to load or fail; lazily loaded images are skipped.
+    pub wait_images: bool,
+    /// Wait for stylesheets to load (the window `load` event).
+    pub wait_stylesheets: bool,
+    /// How long to wait after every other condition is met.
+    pub extra_delay: Duration,
+    /// A JavaScript expression that must evaluate to a truthy value,
+    /// e.g. `window.__READY__ === true`. Promises are awaited.
+    pub predicate_js: Option<String>,
+    /// How long to wait before giving up.
+    pub timeout: Duration,
+}
+
+impl Default for ReadyOptions {
+    fn default() -> Self {
+        Self {
+            wait_fonts: true,
+            wait_images: true,
+            wait_stylesheets: true,
+            extra_delay: Duration::ZERO,
+            predicate_js: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ReadyOptions {
+    /// Create new readiness options with default values
+    /// (wait for fonts, images and stylesheets, 30 s timeout).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether to wait for web fonts to load.
+    pub fn with_wait_fonts(mut self, wait: bool) -> Self {
+        self.wait_fonts = wait;
+        self
+    }
+
+    /// Set whether to wait for images to load.
+    pub fn with_wait_images(mut self, wait: bool) -> Self {
+        self.wait_images = wait;
+        self
+    }
+
+    /// Set whether to wait for stylesheets to load.
+    pub fn with_wait_stylesheets(mut self, wait: bool) -> Self {
+        self.wait_stylesheets = wait;
+        self
+    }
+
+    /// Set how long to wait after every other condition is met, e.g. for animations to settle.
+    pub fn with_extra_delay(mut self, delay: Duration) -> Self {
+        self.extra_delay = delay;
+        self
+    }
+
+    /// Set a JavaScript expression that must become truthy before the page is ready.
+    pub fn with_predicate(mut self, predicate_js: impl Into<String>) -> Self {
+        self.predicate_js = Some(predicate_js.into());
+        self
+    }
+
+    /// Set how long to wait before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
@@ -0,0 +1,27 @@
+/// An RGBA color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+    /// The alpha component, between 0 and 1.
+    pub a: f64,
+}
+
+impl Rgba {
+    /// A fully transparent color.
+    pub const TRANSPARENT: Rgba = Rgba::new(0, 0, 0, 0.0);
+
+    /// Create a new color.
+    pub const fn new(r: u8, g: u8, b: u8, a: f64) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Whether the color is not fully opaque.
+    pub(crate) fn is_translucent(&self) -> bool {
+        self.a < 1.0
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+/// A way to locate elements on a page.
+///
+/// Plain strings convert into CSS selectors, so `tab.find_element("h1")` keeps working.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    /// A CSS selector, e.g. `#title > span`.
+    Css(String),
+    /// An XPath expression, e.g. `//div[@id="result"]`.
+    XPath(String),
+    /// The innermost rendered elements whose visible text equals (`exact`) or contains `text`.
+    Text { text: String, exact: bool },
+}
+
+impl Selector {
+    /// Create a CSS selector.
+    pub fn css(selector: impl Into<String>) -> Self {
+        Selector::Css(selector.into())
+    }
+
+    /// Create an XPath selector.
+    pub fn xpath(expression: impl Into<String>) -> Self {
+        Selector::XPath(expression.into())
+    }
+
+    /// Match elements whose trimmed visible text is exactly `text`.
+    pub fn text(text: impl Into<String>) -> Self {
+        Selector::Text { text: text.into(), exact: true }
+    }
+
+    /// Match elements whose visible text contains `text`.
+    pub fn text_contains(text: impl Into<String>) -> Self {
+        Selector::Text { text: text.into(), exact: false }
+    }
+
+    /// A JavaScript expression evaluating to an array of the matching nodes.
+    pub(crate) fn to_js(&self) -> String {
+        match self {
+            Selector::Css(selector) => format!(
+                "Array.from(document.querySelectorAll({selector}))",
+                selector = serde_json::Value::from(selector.as_str())
+            ),
+            Selector::XPath(expression) => format!(
+                r#"
+    (() => {{
+        const result = document.evaluate(
+            {expression}, document, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null
+        );
+        const nodes = [];
+        for (let i = 0; i < result.snapshotLength; i++) nodes.push(result.snapshotItem(i));
+        return nodes;
+    }})()
+    "#,
+                expression = serde_json::Value::from(expression.as_str())
+            ),
+            Selector::Text { text, exact } => format!(
+                r#"
+    (() => {{
+        const needle = {text};
+        // Elements without boxes (script, style, template, display: none) are not rendered.
+        const matches = el => {{
+            if (el.getClientRects().length === 0) return false;
+            const text = (el.innerText ?? el.textContent ?? '').trim();
+            return {exact} ? text === needle : text.includes(needle);
+        }};
+        return Array.from(document.querySelectorAll('body, body *'))
+            .filter(el => matches(el) && !Array.from(el.children).some(matches));
+    }})()
+    "#,
+                text = serde_json::Value::from(text.as_str())
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Selector::Css(selector) => write!(f, "{selector}"),
+            Selector::XPath(expression) => write!(f, "xpath={expression}"),
+            Selector::Text { text, exact: true } => write!(f, "text={text:?}"),
+            Selector::Text { text, exact: false } => write!(f, "text*={text:?}"),
+        }
+    }
+}
+
+impl From<&str> for Selector {
+    fn from(selector: &str) -> Self {
+        Selector::Css(selector.to_string())
+    }
+}
+
+impl From<String> for Selector {
+    fn from(selector: String) -> Self {
+        Selector::Css(selector)
+    }
+}
+
+impl From<&String> for Selector {
+    fn from(selector: &String) -> Self {
+        Selector::Css(selector.clone())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::ReadyOptions;
+
+/// Configuration options for [`Tab::set_content_with_options`](crate::Tab::set_content_with_options).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetContentOptions {
+    /// The URL that relative URLs in the content resolve against.
+    pub base_url: Option<String>,
+    /// The conditions to wait for once the content is set.
+    pub ready: ReadyOptions,
+}
+
+impl SetContentOptions {
+    /// Create new options with default values (no base URL, default readiness conditions).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the URL that relative URLs in the content resolve against,
+    /// e.g. `https://example.com/assets/` for `<img src="logo.png">`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Set the conditions to wait for once the content is set.
+    pub fn with_ready(mut self, ready: ReadyOptions) -> Self {
+        self.ready = ready;
+        self
+    }
+
+    /// Insert a `<base href>` element for the base URL into `html`, if one is set.
+    ///
+    /// The element goes right after the opening `<head>` tag, or after `<html>` or the
+    /// doctype when there is no head, so that it applies before any relative URL.
+    pub(crate) fn apply(&self, html: &str) -> String {
+        let Some(base_url) = &self.base_url else {
+            return html.to_string();
+        };
+
+        let href = base_url
+            .replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('<', "&lt;");
+        let base = format!(r#"<base href="{href}">"#);
+
+        let lower = html.to_ascii_lowercase();
+        let after_tag = |tag: &str| {
+            lower
+                .match_indices(tag)
+                .find(|(i, _)| matches!(lower.as_bytes().get(i + tag.len()), Some(b'>' | b' ' | b'\t' | b'\n' | b'\r')))
+                .and_then(|(i, _)| lower[i..].find('>').map(|end| i + end + 1))
+        };
+        let at = after_tag("<head")
+            .or_else(|| after_tag("<html"))
+            .or_else(|| after_tag("<!doctype"))
+            .unwrap_or(0);
+
+        format!("{}{base}{}", &html[..at], &html[at..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(html: &str) -> String {
+        SetContentOptions::new()
+            .with_base_url("https://example.com/")
+            .apply(html)
+    }
+
+    #[test]
+    fn without_base_url_leaves_html_unchanged() {
+        assert_eq!(SetContentOptions::new().apply("<p>Hi</p>"), "<p>Hi</p>");
+    }
+
+    #[test]
+    fn inserts_after_head() {
+        assert_eq!(
+            apply("<html><head><title>Hi</title></head></html>"),
+            r#"<html><head><base href="https://example.com/"><title>Hi</title></head></html>"#
+        );
+    }
+
+    #[test]
+    fn header_is_not_head() {
+        assert_eq!(
+            apply("<html><body><header>Hi</header></body></html>"),
+            r#"<html><base href="https://example.com/"><body><header>Hi</header></body></html>"#
+        );
+    }
+
+    #[test]
+    fn missing_head_inserts_after_html() {
+        assert_eq!(
+            apply(r#"<!DOCTYPE html><html lang="en"><body>Hi</body></html>"#),
+            r#"<!DOCTYPE html><html lang="en"><base href="https://example.com/"><body>Hi</body></html>"#
+        );
+    }
+
+    #[test]
+    fn doctype_only_inserts_after_doctype() {
+        assert_eq!(
+            apply("<!doctype html><p>Hi</p>"),
+            r#"<!doctype html><base href="https://example.com/"><p>Hi</p>"#
+        );
+    }
+
+    #[test]
+    fn fragment_inserts_at_start() {
+        assert_eq!(apply("<p>Hi</p>"), r#"<base href="https://example.com/"><p>Hi</p>"#);
+    }
+
+    #[test]
+    fn matches_upper_case_tags() {
+        assert_eq!(
+            apply("<HTML><HEAD id=\"h\"></HEAD></HTML>"),
+            r#"<HTML><HEAD id="h"><base href="https://example.com/"></HEAD></HTML>"#
+        );
+    }
+
+    #[test]
+    fn escapes_base_url() {
+        let html = SetContentOptions::new()
+            .with_base_url(r#"https://example.com/?a=1&b="2""#)
+            .apply("<p>Hi</p>");
+        assert_eq!(html, r#"<base href="https://example.com/?a=1&amp;b=&quot;2&quot;"><p>Hi</p>"#);
+    }
+}
@@ -1,18 +1,110 @@
 use std::sync::Arc;
-use serde_json::json;
-use anyhow::{Context, Result};
+use std::path::Path;
+use base64::Engine;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use futures_util::Stream;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use serde_json::{json, Value};
+use serde::{de::DeserializeOwned, Serialize};
 
+use crate::Rgba;
+use crate::CdpEvent;
+use crate::cdp_event;
+use crate::capture_report::DiagnosticsRecorder;
+use crate::Viewport;
+use crate::Selector;
+use crate::PdfOptions;
 use crate::general_utils;
-use crate::element::Element;
+use crate::CaptureOptions;
+use crate::ReadyOptions;
+use crate::SetContentOptions;
+use crate::MediaEmulation;
 use crate::transport::Transport;
 use crate::general_utils::next_id;
-use crate::transport_actor::TransportResponse;
+use crate::error::{Error, Result};
+use crate::image_format::ImageFormat;
+use crate::{ElementState, WaitOptions};
+use crate::input::{Keyboard, Mouse};
+use crate::{NavigateOptions, WaitUntil};
+use crate::element::{Element, ScreenshotConfig};
+
+/// Starts waiting for the page to become ready, keeping the current step in `pending`.
+/// Called with the readiness options and a predicate function.
+const READY_SCRIPT: &str = r#"
+(options, predicate) => {
+    const state = { pending: 'document' };
+    const event = (target, ...names) => new Promise(resolve => {
+        names.forEach(name => target.addEventListener(name, resolve, { once: true }));
+    });
+
+    state.done = (async () => {
+        if (document.readyState === 'loading') {
+            await event(document, 'DOMContentLoaded');
+        }
+
+        if (options.stylesheets && document.readyState !== 'complete') {
+            state.pending = 'stylesheets';
+            await event(window, 'load');
+        }
+
+        if (options.images) {
+            state.pending = 'images';
+            await Promise.all(Array.from(document.images)
+                .filter(img => !img.complete && img.loading !== 'lazy')
+                .map(img => event(img, 'load', 'error')));
+        }
+
+        if (options.fonts) {
+            state.pending = 'fonts';
+            await document.fonts.ready;
+        }
+
+        if (predicate) {
+            state.pending = 'predicate';
+            while (!(await predicate())) {
+                await new Promise(resolve => setTimeout(resolve, 50));
+            }
+        }
+
+        if (options.extraDelay > 0) {
+            state.pending = 'extra delay';
+            await new Promise(resolve => setTimeout(resolve, options.extraDelay));
+        }
+
+        state.pending = 'rendering';
+        await new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(resolve)));
+        state.pending = null;
+    })();
+
+    return state;
+}
+"#;
+
+/// The page global that holds the readiness state of the current content.
+const READY_STATE: &str = "globalThis[Symbol.for('cdp-html-shot.ready')]";
+
+/// A rectangular region of the page, in CSS pixels.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Clip {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
 
 /// A tab instance.
 pub struct Tab {
     pub(crate) transport: Arc<Transport>,
     pub(crate) session_id: String,
     pub(crate) target_id: String,
+    /// The current `Emulation.setDeviceMetricsOverride` state; a zero size keeps the window size.
+    metrics: Mutex<Viewport>,
+    /// The current `Emulation.setDefaultBackgroundColorOverride` color; `None` is the default white.
+    background: Mutex<Option<Rgba>>,
+    /// How long to wait for the browser to answer a command.
+    command_timeout: Mutex<Duration>,
 }
 
 impl Tab {
@@ -32,44 +124,108 @@ impl Tab {
     }
     ```
     */
-    pub(crate) async fn new(transport: Arc<Transport>) -> Result<Self> {
-        let TransportResponse::Response(res) = transport.send(json!({
+    pub(crate) async fn new(transport: Arc<Transport>, viewport: Option<Viewport>) -> Result<Self> {
+        let res = transport.call(json!({
             "id": next_id(),
             "method": "Target.createTarget",
             "params": {
                 "url": "about:blank"
             }
-        })).await? else { panic!() };
+        })).await?;
 
-        let target_id = res
-            .result
-            .get("targetId")
-            .context("Failed to get targetId")?
+        let target_id = res["targetId"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| Error::protocol("Failed to get targetId"))?;
+
+        match Self::attach(transport.clone(), target_id, viewport).await {
+            Ok(tab) => Ok(tab),
+            Err(e) => {
+                // Nobody gets a tab to close, so close the target here; its own error is secondary.
+                let _ = transport.call(json!({
+                    "id": next_id(),
+                    "method": "Target.closeTarget",
+                    "params": {
+                        "targetId": target_id
+                    }
+                })).await;
+
+                Err(e)
+            }
+        }
+    }
 
-        let TransportResponse::Response(res) = transport.send(json!({
+    /// Attach to a newly created target and apply the initial viewport.
+    async fn attach(transport: Arc<Transport>, target_id: &str, viewport: Option<Viewport>) -> Result<Self> {
+        let res = transport.call(json!({
             "id": next_id(),
             "method": "Target.attachToTarget",
             "params": {
-                "targetId": target_id
+                "targetId": target_id,
+                "flatten": true
             }
-        })).await? else { panic!() };
+        })).await?;
 
-        let session_id = res
-            .result["sessionId"]
+        let session_id = res["sessionId"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| Error::protocol("Failed to get sessionId"))?;
 
-        Ok(Self {
+        let command_timeout = transport.command_timeout();
+        let tab = Self {
             transport,
             session_id: String::from(session_id),
             target_id: String::from(target_id),
-        })
+            metrics: Mutex::new(Viewport::new(0, 0)),
+            background: Mutex::new(None),
+            command_timeout: Mutex::new(command_timeout),
+        };
+
+        if let Some(viewport) = viewport {
+            tab.set_viewport(viewport).await?;
+        }
+
+        Ok(tab)
+    }
+
+    /// Send a command to this tab's session and return its `result` object.
+    pub(crate) async fn send_cmd(&self, method: &str, params: Value) -> Result<Value> {
+        let msg = json!({
+            "id": next_id(),
+            "method": method,
+            "params": params
+        });
+
+        let timeout = *self.command_timeout.lock().unwrap();
+        self.transport.send(Some(&self.session_id), msg, timeout).await
+    }
+
+    /**
+    Set how long to wait for the browser to answer each command sent by this tab.
+
+    Overrides [`BrowserBuilder::command_timeout`](crate::BrowserBuilder::command_timeout),
+    e.g. for screenshots of very tall elements.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use std::time::Duration;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_command_timeout(Duration::from_secs(60));
+        Ok(())
+    }
+    ```
+    */
+    pub fn set_command_timeout(&self, timeout: Duration) -> &Self {
+        *self.command_timeout.lock().unwrap() = timeout;
+        self
     }
 
     /**
-    Set the content of the tab.
+    Set the content of the tab and wait until its stylesheets, images and web fonts have loaded.
 
     # Example
     ```no_run
@@ -86,101 +242,111 @@ impl Tab {
     ```
     */
     pub async fn set_content(&self, content: &str) -> Result<&Self> {
-        let content = match (content.contains('`'), content.contains("${")) {
-            (true, true) => &content.replace('`', "${BACKTICK}").replace("${", "$ {"),
-            (true, false) => &content.replace('`', "${BACKTICK}"),
-            (false, true) => &content.replace("${", "$ {"),
-            (false, false) => content,
+        self.set_content_with_options(content, SetContentOptions::default()).await
+    }
+
+    /**
+    Set the content of the tab with the given options.
+
+    The page has no URL of its own, so relative URLs only resolve when a base URL is set.
+    Fails with [`Error::ReadyTimeout`] when the readiness conditions are not met in time.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, ReadyOptions, SetContentOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content_with_options(
+            r#"<link rel="stylesheet" href="style.css"><img src="logo.png">"#,
+            SetContentOptions::new().with_base_url("https://example.com/assets/"),
+        ).await?;
+        tab.set_content_with_options(
+            r#"<div id="chart"></div><script>setTimeout(() => window.__READY__ = true, 500)</script>"#,
+            SetContentOptions::new().with_ready(ReadyOptions::new().with_predicate("window.__READY__ === true")),
+        ).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn set_content_with_options(&self, content: &str, options: SetContentOptions) -> Result<&Self> {
+        let res = self.send_cmd("Page.getFrameTree", json!({})).await?;
+        let frame_id = res["frameTree"]["frame"]["id"]
+            .as_str()
+            .ok_or_else(|| Error::protocol("Failed to get frameId"))?;
+
+        self.send_cmd("Page.setDocumentContent", json!({
+            "frameId": frame_id,
+            "html": options.apply(content)
+        })).await?;
+
+        self.wait_until_ready(&options.ready).await?;
+
+        Ok(self)
+    }
+
+    /// Wait for the readiness conditions of the current content.
+    ///
+    /// The page does the waiting; this polls it in short slices so that long waits
+    /// do not run into the command timeout.
+    async fn wait_until_ready(&self, ready: &ReadyOptions) -> Result<()> {
+        let settings = json!({
+            "stylesheets": ready.wait_stylesheets,
+            "images": ready.wait_images,
+            "fonts": ready.wait_fonts,
+            "extraDelay": ready.extra_delay.as_millis() as u64
+        });
+        let predicate = match &ready.predicate_js {
+            Some(predicate) => format!("async () => ({predicate})"),
+            None => "null".to_string(),
         };
 
-        let expression = format!(
-            r#"
-    (async () => {{
-        try {{
-            const BACKTICK = '`';
-            document.open();
-            document.write(String.raw`{content}`);
-            document.close();
-
-            await Promise.race([
-                new Promise((resolve) => {{
-                    const checkResources = async () => {{
-                        if (document.readyState !== 'complete') {{
-                            return false;
-                        }}
-
-                        const images = Array.from(document.images);
-                        const imagePromises = images.map(img => {{
-                            if (img.complete) return Promise.resolve();
-                            return new Promise(resolve => {{
-                                img.onload = resolve;
-                                img.onerror = resolve;
-                            }});
-                        }});
-
-                        const styleSheets = Array.from(document.styleSheets);
-                        const stylePromises = styleSheets.map(sheet => {{
-                            if (!sheet.href) return Promise.resolve();
-                            return new Promise(resolve => {{
-                                const link = document.querySelector(`link[href="${{sheet.href}}"]`);
-                                if (link.sheet) resolve();
-                                else {{
-                                    link.onload = resolve;
-                                    link.onerror = resolve;
-                                }}
-                            }});
-                        }});
-
-                        await Promise.all([...imagePromises, ...stylePromises]);
-
-                        return new Promise(resolve => {{
-                            requestAnimationFrame(() => {{
-                                requestAnimationFrame(resolve);
-                            }});
-                        }});
-                    }};
-
-                    checkResources().then(resolved => {{
-                        if (!resolved) {{
-                            window.addEventListener('load', () => {{
-                                checkResources().then(resolve);
-                            }});
-                        }} else {{
-                            resolve(true);
-                        }}
-                    }});
-                }}),
-
-                new Promise((_, reject) => {{
-                    setTimeout(() => reject(new Error('Timeout')), 30000);
-                }})
-            ]);
-
-            return 'Page loaded successfully';
-        }} catch (error) {{
-            throw new Error(`Failed to set content: ${{error.message}}`);
-        }}
-    }})();
-    "#
-        );
+        let res = self.send_cmd("Runtime.evaluate", json!({
+            "expression": format!("{READY_STATE} = ({READY_SCRIPT})({settings}, {predicate}); undefined")
+        })).await?;
+        if let Some(details) = res.get("exceptionDetails") {
+            return Err(general_utils::js_exception(details));
+        }
 
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "Runtime.evaluate",
-            "params": {
-                "expression": expression,
+        let start = Instant::now();
+        loop {
+            let waited = start.elapsed();
+            let slice = ready.timeout.saturating_sub(waited).min(Duration::from_secs(1));
+
+            let res = self.send_cmd("Runtime.evaluate", json!({
+                "expression": format!(
+                    "Promise.race([{READY_STATE}.done, new Promise(resolve => setTimeout(resolve, {}))]).then(() => {READY_STATE}.pending)",
+                    slice.as_millis()
+                ),
                 "awaitPromise": true,
+                "returnByValue": true
+            })).await?;
+            if let Some(details) = res.get("exceptionDetails") {
+                return Err(general_utils::js_exception(details));
             }
-        }).to_string();
 
-        general_utils::send_and_get_msg(self.transport.clone(), msg_id, &self.session_id, msg).await?;
+            let Some(pending) = res["result"]["value"].as_str() else {
+                return Ok(());
+            };
 
-        Ok(self)
+            let waited = start.elapsed();
+            if waited >= ready.timeout {
+                return Err(Error::ReadyTimeout {
+                    waited,
+                    pending: pending.to_string(),
+                });
+            }
+        }
     }
 
     /**
-    Find an element by CSS selector.
+    Evaluate a JavaScript expression in the page and deserialize its result.
+
+    Promises are awaited. A thrown exception is returned as [`Error::JavaScript`],
+    including the JavaScript stack trace when available.
 
     # Example
     ```no_run
@@ -191,50 +357,72 @@ impl Tab {
     async fn main() -> Result<()> {
         let browser = Browser::new().await?;
         let tab = browser.new_tab().await?;
-        let element = tab.find_element("h1").await?;
+        tab.set_content("<h1>Hello world!</h1>").await?;
+
+        let title: String = tab.evaluate("document.querySelector('h1').textContent").await?;
+        let count: u32 = tab.evaluate("Promise.resolve(document.images.length)").await?;
         Ok(())
     }
     ```
     */
-    pub async fn find_element(&self, selector: &str) -> Result<Element> {
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "DOM.getDocument",
-            "params": {}
-        }).to_string();
+    pub async fn evaluate<T: DeserializeOwned>(&self, expression: &str) -> Result<T> {
+        let res = self.send_cmd("Runtime.evaluate", json!({
+            "expression": expression,
+            "awaitPromise": true,
+            "returnByValue": true
+        })).await?;
 
-        let res = general_utils::send_and_get_msg(self.transport.clone(), msg_id, &self.session_id, msg).await?;
+        if let Some(details) = res.get("exceptionDetails") {
+            return Err(general_utils::js_exception(details));
+        }
 
-        let msg = general_utils::serde_msg(&res);
-        let node_id = msg["result"]["root"]["nodeId"]
-            .as_u64()
-            .unwrap();
+        // `undefined` has no value, so deserialize it as `null`.
+        let value = res["result"].get("value").cloned().unwrap_or(Value::Null);
+        Ok(serde_json::from_value(value)?)
+    }
 
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "DOM.querySelector",
-            "params": {
-                "nodeId": node_id,
-                "selector": selector
-            }
-        }).to_string();
+    /**
+    Call a JavaScript function in the page with the given arguments and deserialize its result.
+
+    `args` is serialized to JSON. A tuple, array or `Vec` is spread into the function's
+    parameters, `()` passes no arguments, and any other value is passed as the only argument.
 
-        let res = general_utils::send_and_get_msg(self.transport.clone(), msg_id, &self.session_id, msg).await?;
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
 
-        let msg = general_utils::serde_msg(&res);
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
 
-        let node_id = match msg["result"]["nodeId"].as_u64() {
-            Some(node_id) => node_id,
-            None => return Err(anyhow::anyhow!("Element not found")),
+        let sum: i32 = tab.call_function("(a, b) => a + b", (1, 2)).await?;
+        assert_eq!(sum, 3);
+        Ok(())
+    }
+    ```
+    */
+    pub async fn call_function<T: DeserializeOwned>(
+        &self,
+        function: &str,
+        args: impl Serialize,
+    ) -> Result<T> {
+        let args = match serde_json::to_value(args)? {
+            Value::Null => Value::Array(Vec::new()),
+            Value::Array(args) => Value::Array(args),
+            arg => Value::Array(vec![arg]),
         };
 
-        Element::new(self, node_id).await
+        self.evaluate(&format!("({function}).apply(globalThis, {args})")).await
     }
 
     /**
-    Close the tab.
+    Add a script that runs in every new document before the page's own scripts.
+
+    The script also runs right away in the current document, so globals it defines
+    are visible to content loaded later with [`set_content`](Self::set_content).
+    Returns the script identifier assigned by the browser.
 
     # Example
     ```no_run
@@ -245,67 +433,237 @@ impl Tab {
     async fn main() -> Result<()> {
         let browser = Browser::new().await?;
         let tab = browser.new_tab().await?;
-        tab.close().await?;
+        tab.add_init_script("window.__THEME__ = 'dark';").await?;
+        tab.set_content("<script>document.write(window.__THEME__)</script>").await?;
         Ok(())
     }
     ```
     */
-    pub async fn activate(&self) -> Result<&Self> {
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "Target.activateTarget",
-            "params": {
-                "targetId": self.target_id
-            }
-        }).to_string();
+    pub async fn add_init_script(&self, source: &str) -> Result<String> {
+        let res = self.send_cmd("Page.addScriptToEvaluateOnNewDocument", json!({
+            "source": source
+        })).await?;
+
+        let identifier = res["identifier"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::protocol("Failed to get script identifier"))?;
+
+        let res = self.send_cmd("Runtime.evaluate", json!({
+            "expression": source
+        })).await?;
+        if let Some(details) = res.get("exceptionDetails") {
+            return Err(general_utils::js_exception(details));
+        }
+
+        Ok(identifier)
+    }
+
+    /**
+    Set the device scale factor (device pixel ratio) of the tab.
+
+    Screenshots are taken in CSS pixels and scaled by this factor,
+    so a 400×300 element is captured as 800×600 pixels with a factor of `2.0`.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_device_scale_factor(2.0).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn set_device_scale_factor(&self, factor: f64) -> Result<&Self> {
+        let viewport = Viewport {
+            scale: factor,
+            ..*self.metrics.lock().unwrap()
+        };
+
+        self.set_viewport(viewport).await
+    }
+
+    /**
+    Set the viewport size of the tab.
 
-        general_utils::send_and_get_msg(self.transport.clone(), msg_id, &self.session_id, msg).await?;
+    Layout, media queries and viewport screenshots use this size.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, Viewport};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_viewport(Viewport {
+            width: 390,
+            height: 844,
+            mobile: true,
+            scale: 3.0,
+        }).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn set_viewport(&self, viewport: Viewport) -> Result<&Self> {
+        self.send_cmd("Emulation.setDeviceMetricsOverride", json!({
+            "width": viewport.width,
+            "height": viewport.height,
+            "deviceScaleFactor": viewport.scale,
+            "mobile": viewport.mobile
+        })).await?;
 
+        *self.metrics.lock().unwrap() = viewport;
         Ok(self)
     }
 
     /**
-    Navigate to a URL.
+    Override the default background color of the page.
 
-    # Warning
+    Pages without a background of their own are rendered on top of this color.
+    Use [`Rgba::TRANSPARENT`] to capture PNG or WebP images with an alpha channel,
+    and `None` to restore the default white background.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, Rgba};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_background_color(Some(Rgba::TRANSPARENT)).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn set_background_color(&self, color: Option<Rgba>) -> Result<&Self> {
+        let params = match color {
+            Some(color) => json!({
+                "color": {
+                    "r": color.r,
+                    "g": color.g,
+                    "b": color.b,
+                    "a": color.a
+                }
+            }),
+            None => json!({}),
+        };
+
+        self.send_cmd("Emulation.setDefaultBackgroundColorOverride", params).await?;
+
+        *self.background.lock().unwrap() = color;
+        Ok(self)
+    }
 
-    This API does not wait for the page to load, it is only used to navigate to local HTML files,
-    which is convenient for getting font and other resources.
+    /**
+    Emulate a CSS media type and media features such as `prefers-color-scheme`.
+
+    Fields left as `None` restore the browser's own value.
 
     # Example
     ```no_run
-    use cdp_html_shot::Browser;
+    use cdp_html_shot::{Browser, ColorScheme, MediaEmulation, MediaType};
     use anyhow::Result;
-    use tokio::time;
 
     #[tokio::main]
     async fn main() -> Result<()> {
         let browser = Browser::new().await?;
         let tab = browser.new_tab().await?;
-        tab.goto("https://www.rust-lang.org/").await?;
-        time::sleep(time::Duration::from_secs(5)).await;
+        tab.emulate_media(MediaEmulation {
+            media_type: Some(MediaType::Print),
+            color_scheme: Some(ColorScheme::Dark),
+            ..Default::default()
+        }).await?;
         Ok(())
     }
     ```
     */
-    pub async fn goto(&self, url: &str) -> Result<&Self> {
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "Page.navigate",
-            "params": {
-                "url": url
-            }
-        }).to_string();
+    pub async fn emulate_media(&self, media: MediaEmulation) -> Result<&Self> {
+        self.send_cmd("Emulation.setEmulatedMedia", media.to_params()).await?;
 
-        general_utils::send_and_get_msg(self.transport.clone(), msg_id, &self.session_id, msg).await?;
+        Ok(self)
+    }
+
+    /// Start recording console messages, exceptions and log entries.
+    pub(crate) async fn record_diagnostics(&self) -> Result<DiagnosticsRecorder> {
+        let recorder = DiagnosticsRecorder::new(self.transport.subscribe_session(&self.session_id).await?);
+
+        self.send_cmd("Runtime.enable", json!({})).await?;
+        self.send_cmd("Log.enable", json!({})).await?;
+
+        Ok(recorder)
+    }
+
+    /// Apply the tab-level settings of the capture options before loading content.
+    pub(crate) async fn apply_options(&self, options: &CaptureOptions) -> Result<&Self> {
+        if let Some(viewport) = options.viewport {
+            self.set_viewport(viewport).await?;
+        }
+
+        if let Some(factor) = options.device_scale_factor {
+            self.set_device_scale_factor(factor).await?;
+        }
+
+        if let Some(media) = options.media {
+            self.emulate_media(media).await?;
+        }
 
         Ok(self)
     }
 
     /**
-    Close the tab.
+    Find an element by CSS selector, XPath or text.
+
+    Accepts a `&str` CSS selector or any [`Selector`].
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, Selector};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        let element = tab.find_element("h1").await?;
+        let element = tab.find_element(Selector::xpath("//h1[1]")).await?;
+        let element = tab.find_element(Selector::text("Hello world!")).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn find_element(&self, selector: impl Into<Selector>) -> Result<Element<'_>> {
+        let selector = selector.into();
+        let node_id = match &selector {
+            Selector::Css(css) => {
+                let res = self.send_cmd("DOM.querySelector", json!({
+                    "nodeId": self.document_node_id().await?,
+                    "selector": css
+                })).await?;
+                res["nodeId"].as_u64().filter(|&id| id != 0)
+            }
+            _ => self.query_js(&selector).await?.first().copied(),
+        };
+
+        match node_id {
+            Some(node_id) => Element::new(self, node_id).await,
+            None => Err(Error::ElementNotFound { selector: selector.to_string() }),
+        }
+    }
+
+    /**
+    Find all elements matching a selector, in document order.
+
+    Returns an empty list if nothing matches.
 
     # Example
     ```no_run
@@ -316,22 +674,733 @@ impl Tab {
     async fn main() -> Result<()> {
         let browser = Browser::new().await?;
         let tab = browser.new_tab().await?;
-        tab.close().await?;
+        tab.set_content("<ul><li>One</li><li>Two</li></ul>").await?;
+
+        for (i, element) in tab.find_elements("li").await?.iter().enumerate() {
+            element.save_to(format!("item{i}.png")).await?;
+        }
         Ok(())
     }
     ```
     */
-    pub async fn close(&self) -> Result<()> {
-        let msg_id = next_id();
-        let msg = json!({
-            "id": msg_id,
-            "method": "Target.closeTarget",
-            "params": {
-                "targetId": self.target_id
+    pub async fn find_elements(&self, selector: impl Into<Selector>) -> Result<Vec<Element<'_>>> {
+        let selector = selector.into();
+        let node_ids = match &selector {
+            Selector::Css(css) => {
+                let res = self.send_cmd("DOM.querySelectorAll", json!({
+                    "nodeId": self.document_node_id().await?,
+                    "selector": css
+                })).await?;
+
+                res["nodeIds"]
+                    .as_array()
+                    .ok_or_else(|| Error::protocol("Failed to get nodeIds"))?
+                    .iter()
+                    .filter_map(|id| id.as_u64())
+                    .collect()
+            }
+            _ => self.query_js(&selector).await?,
+        };
+
+        let mut elements = Vec::with_capacity(node_ids.len());
+        for node_id in node_ids {
+            elements.push(Element::new(self, node_id).await?);
+        }
+
+        Ok(elements)
+    }
+
+    /**
+    Wait until an element matching the selector reaches the given state.
+
+    Useful for pages that build their DOM asynchronously.
+    Fails with [`Error::WaitTimeout`] if the state is not reached within the timeout.
+
+    # Example
+    ```no_run
+    use std::time::Duration;
+    use cdp_html_shot::{Browser, ElementState, WaitOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content(r#"<script>setTimeout(() => document.body.innerHTML = '<h1>Hi</h1>', 500)</script>"#).await?;
+
+        tab.wait_for_selector("h1", WaitOptions::new()
+            .with_state(ElementState::Visible)
+            .with_timeout(Duration::from_secs(5))
+        ).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn wait_for_selector(
+        &self,
+        selector: impl Into<Selector>,
+        options: WaitOptions,
+    ) -> Result<&Self> {
+        let selector = selector.into();
+        let expression = format!(
+            r#"
+    (() => {{
+        const node = ({nodes})[0];
+        if (!node) return 'detached';
+        const el = node.nodeType === Node.ELEMENT_NODE ? node : node.parentElement;
+        if (!el) return 'hidden';
+        const style = getComputedStyle(el);
+        const rect = el.getBoundingClientRect();
+        const visible = style.display !== 'none'
+            && style.visibility !== 'hidden'
+            && rect.width > 0
+            && rect.height > 0;
+        return visible ? 'visible' : 'hidden';
+    }})()
+    "#,
+            nodes = selector.to_js()
+        );
+
+        let start = Instant::now();
+        loop {
+            let res = self.send_cmd("Runtime.evaluate", json!({
+                "expression": expression,
+                "returnByValue": true
+            })).await?;
+
+            if let Some(details) = res.get("exceptionDetails") {
+                return Err(Error::InvalidArgument(format!(
+                    "Invalid selector {selector}: {}",
+                    details["exception"]["description"].as_str().unwrap_or_default()
+                )));
+            }
+
+            let observed = ElementState::from_probe(res["result"]["value"].as_str().unwrap_or_default());
+            if options.state.is_satisfied_by(observed) {
+                return Ok(self);
             }
-        }).to_string();
 
-        general_utils::send_and_get_msg(self.transport.clone(), msg_id, &self.session_id, msg).await?;
+            let waited = start.elapsed();
+            if waited >= options.timeout {
+                return Err(Error::WaitTimeout {
+                    selector: selector.to_string(),
+                    state: options.state,
+                    waited,
+                    last_state: observed,
+                });
+            }
+
+            tokio::time::sleep(options.poll.min(options.timeout - waited)).await;
+        }
+    }
+
+    /// Resolve a selector by evaluating it in the page and requesting each node.
+    async fn query_js(&self, selector: &Selector) -> Result<Vec<u64>> {
+        let expression = selector.to_js();
+
+        // `DOM.requestNode` only works once the document has been requested.
+        self.document_node_id().await?;
+
+        let res = self.send_cmd("Runtime.evaluate", json!({
+            "expression": expression
+        })).await?;
+
+        if let Some(details) = res.get("exceptionDetails") {
+            return Err(Error::InvalidArgument(format!(
+                "Invalid selector {selector}: {}",
+                details["exception"]["description"].as_str().unwrap_or_default()
+            )));
+        }
+
+        let array_id = res["result"]["objectId"]
+            .as_str()
+            .ok_or_else(|| Error::protocol("Failed to get objectId"))?;
+
+        let res = self.send_cmd("Runtime.getProperties", json!({
+            "objectId": array_id,
+            "ownProperties": true
+        })).await?;
+
+        let mut node_ids = Vec::new();
+        for property in res["result"].as_array().into_iter().flatten() {
+            // Skip `length` and other non-index properties.
+            if property["name"].as_str().is_none_or(|name| name.parse::<usize>().is_err()) {
+                continue;
+            }
+            let Some(object_id) = property["value"]["objectId"].as_str() else {
+                continue;
+            };
+
+            let res = self.send_cmd("DOM.requestNode", json!({
+                "objectId": object_id
+            })).await?;
+            if let Some(node_id) = res["nodeId"].as_u64() {
+                node_ids.push(node_id);
+            }
+        }
+
+        self.send_cmd("Runtime.releaseObject", json!({
+            "objectId": array_id
+        })).await?;
+
+        Ok(node_ids)
+    }
+
+    /// Get the node id of the document root, which also enables the DOM domain.
+    async fn document_node_id(&self) -> Result<u64> {
+        let res = self.send_cmd("DOM.getDocument", json!({})).await?;
+
+        res["root"]["nodeId"]
+            .as_u64()
+            .ok_or_else(|| Error::protocol("Failed to get root nodeId"))
+    }
+
+    /**
+    Capture a screenshot of the visible viewport.
+
+    Returns the base64-encoded image data.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, ScreenshotConfig};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content("<h1>Hello world!</h1>").await?;
+        let base64 = tab.screenshot(ScreenshotConfig::default()).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn screenshot(&self, config: ScreenshotConfig) -> Result<String> {
+        let metrics = self.send_cmd("Page.getLayoutMetrics", json!({})).await?;
+        let viewport = &metrics["cssVisualViewport"];
+
+        let clip = Clip {
+            x: viewport["pageX"].as_f64().unwrap_or_default(),
+            y: viewport["pageY"].as_f64().unwrap_or_default(),
+            width: viewport["clientWidth"]
+                .as_f64()
+                .ok_or_else(|| Error::protocol("Failed to get viewport width"))?,
+            height: viewport["clientHeight"]
+                .as_f64()
+                .ok_or_else(|| Error::protocol("Failed to get viewport height"))?,
+        };
+
+        self.capture(&config, clip).await
+    }
+
+    /**
+    Capture a screenshot of the whole scrollable page.
+
+    Returns the base64-encoded image data.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, ScreenshotConfig};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content("<h1>Hello world!</h1>").await?;
+        let base64 = tab.screenshot_full_page(ScreenshotConfig::default()).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn screenshot_full_page(&self, config: ScreenshotConfig) -> Result<String> {
+        let metrics = self.send_cmd("Page.getLayoutMetrics", json!({})).await?;
+        let content = match metrics.get("cssContentSize") {
+            Some(size) => size,
+            None => &metrics["contentSize"],
+        };
+
+        let clip = Clip {
+            x: 0.0,
+            y: 0.0,
+            width: content["width"]
+                .as_f64()
+                .ok_or_else(|| Error::protocol("Failed to get content width"))?,
+            height: content["height"]
+                .as_f64()
+                .ok_or_else(|| Error::protocol("Failed to get content height"))?,
+        };
+
+        self.capture(&config, clip).await
+    }
+
+    /**
+    Capture a screenshot of the whole scrollable page and save it to `path`.
+
+    The image format is chosen from the file extension (`png`, `jpg`/`jpeg` or `webp`).
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content("<h1>Hello world!</h1>").await?;
+        tab.save_to("page.png").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn save_to(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = general_utils::format_from_path(path)?;
+
+        let base64 = self.screenshot_full_page(ScreenshotConfig {
+            format,
+            ..Default::default()
+        }).await?;
+
+        std::fs::write(path, general_utils::decode_base64(&base64)?)?;
+        Ok(())
+    }
+
+    /**
+    Render the current page as a PDF.
+
+    Returns the raw PDF bytes. The document is streamed from the browser in chunks,
+    so large documents do not have to fit in a single message.
+
+    # Warning
+    Only in headless mode, Chrome does not support printing to PDF otherwise.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::{Browser, PdfOptions};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.set_content("<h1>Invoice</h1>").await?;
+
+        let options = PdfOptions::new()
+            .with_paper_size(8.27, 11.69)
+            .with_margin(0.4)
+            .with_print_background(true);
+        let pdf = tab.pdf(options).await?;
+        std::fs::write("invoice.pdf", pdf)?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn pdf(&self, options: PdfOptions) -> Result<Vec<u8>> {
+        let res = self.send_cmd("Page.printToPDF", options.to_params()).await?;
+        let handle = res["stream"]
+            .as_str()
+            .ok_or_else(|| Error::protocol("Failed to get stream handle"))?;
+
+        let pdf = self.read_stream(handle).await;
+        let closed = self.send_cmd("IO.close", json!({
+            "handle": handle
+        })).await;
+
+        let pdf = pdf?;
+        closed?;
+        Ok(pdf)
+    }
+
+    /// Read an `IO` stream to the end.
+    async fn read_stream(&self, handle: &str) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        loop {
+            let chunk = self.send_cmd("IO.read", json!({
+                "handle": handle,
+                "size": 1024 * 1024
+            })).await?;
+
+            let text = chunk["data"].as_str().unwrap_or_default();
+            if chunk["base64Encoded"].as_bool().unwrap_or(false) {
+                base64::prelude::BASE64_STANDARD
+                    .decode_vec(text, &mut data)
+                    .map_err(|e| Error::protocol(format!("Invalid base64 data: {e}")))?;
+            } else {
+                data.extend_from_slice(text.as_bytes());
+            }
+
+            if chunk["eof"].as_bool().unwrap_or(true) {
+                return Ok(data);
+            }
+        }
+    }
+
+    /// Get the scroll offset of the layout viewport, in CSS pixels.
+    ///
+    /// Add it to viewport coordinates, such as `DOM.getBoxModel` quads, to get document coordinates.
+    pub(crate) async fn scroll_offset(&self) -> Result<(f64, f64)> {
+        let res = self.send_cmd("Page.getLayoutMetrics", json!({})).await?;
+        let viewport = &res["cssLayoutViewport"];
+
+        Ok((
+            viewport["pageX"].as_f64().unwrap_or_default(),
+            viewport["pageY"].as_f64().unwrap_or_default(),
+        ))
+    }
+
+    /// Send `Page.captureScreenshot` for the given clip and return the base64 data.
+    pub(crate) async fn capture(&self, config: &ScreenshotConfig, clip: Clip) -> Result<String> {
+        let Some(background) = config.background else {
+            return self.capture_screenshot(config, clip).await;
+        };
+
+        if background.is_translucent() && matches!(config.format, ImageFormat::Jpeg { .. }) {
+            return Err(Error::InvalidArgument(
+                "JPEG does not support transparent backgrounds".to_string()
+            ));
+        }
+
+        // Put back whatever override the caller had set, but report a failed capture first.
+        let previous = *self.background.lock().unwrap();
+        self.set_background_color(Some(background)).await?;
+        let res = self.capture_screenshot(config, clip).await;
+        let restored = self.set_background_color(previous).await;
+
+        let image = res?;
+        restored?;
+        Ok(image)
+    }
+
+    async fn capture_screenshot(&self, config: &ScreenshotConfig, clip: Clip) -> Result<String> {
+        let mut params = json!({
+            "format": config.format.as_str(),
+            "fromSurface": true,
+            "captureBeyondViewport": true,
+            "clip": {
+                "x": clip.x,
+                "y": clip.y,
+                "width": clip.width,
+                "height": clip.height,
+                "scale": 1.0
+            }
+        });
+
+        if let Some(quality) = config.format.quality() {
+            params["quality"] = json!(quality);
+        }
+
+        self.activate().await?;
+        let res = self.send_cmd("Page.captureScreenshot", params).await?;
+
+        let base64 = res
+            .get("data")
+            .and_then(|data| data.as_str())
+            .ok_or_else(|| Error::protocol("Failed to get data"))?
+            .to_string();
+
+        Ok(base64)
+    }
+
+    /// Get a handle for dispatching mouse events at viewport coordinates.
+    pub fn mouse(&self) -> Mouse<'_> {
+        Mouse::new(self)
+    }
+
+    /// Get a handle for dispatching key events to the focused element.
+    pub fn keyboard(&self) -> Keyboard<'_> {
+        Keyboard::new(self)
+    }
+
+    /**
+    Send a raw CDP command to this tab and return its `result` object.
+
+    Most events are only sent once their domain is enabled, e.g. with `Runtime.enable`.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use serde_json::json;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        let metrics = tab.send_command("Page.getLayoutMetrics", json!({})).await?;
+        println!("{}", metrics["cssContentSize"]);
+        Ok(())
+    }
+    ```
+    */
+    pub async fn send_command(&self, method: &str, params: Value) -> Result<Value> {
+        self.send_cmd(method, params).await
+    }
+
+    /**
+    Subscribe to the CDP events of this tab and of the browser.
+
+    Only events sent after this call are received.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use futures::StreamExt;
+    use serde_json::json;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        let mut events = tab.events();
+
+        tab.send_command("Network.enable", json!({})).await?;
+        tab.goto("https://www.rust-lang.org/").await?;
+
+        while let Some(event) = events.next().await {
+            println!("{}: {}", event.method, event.params);
+        }
+        Ok(())
+    }
+    ```
+    */
+    pub fn events(&self) -> impl Stream<Item = CdpEvent> + Send + Unpin + 'static {
+        let session_id = self.session_id.clone();
+        cdp_event::into_stream(self.transport.subscribe(), move |event| {
+            event.session_id.as_ref().is_none_or(|id| *id == session_id)
+        })
+    }
+
+    /**
+    Subscribe to one CDP event of this tab or the browser, e.g. `Runtime.consoleAPICalled`.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use futures::StreamExt;
+    use serde_json::json;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        let mut console = tab.on("Runtime.consoleAPICalled");
+
+        tab.send_command("Runtime.enable", json!({})).await?;
+        tab.evaluate::<()>("console.log('Hello from the page')").await?;
+
+        if let Some(event) = console.next().await {
+            println!("{}", event.params["args"][0]["value"]);
+        }
+        Ok(())
+    }
+    ```
+    */
+    pub fn on(&self, method: &str) -> impl Stream<Item = CdpEvent> + Send + Unpin + 'static {
+        let session_id = self.session_id.clone();
+        let method = method.to_string();
+        cdp_event::into_stream(self.transport.subscribe(), move |event| {
+            event.method == method && event.session_id.as_ref().is_none_or(|id| *id == session_id)
+        })
+    }
+
+    /**
+    Close the tab.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.close().await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn activate(&self) -> Result<&Self> {
+        self.send_cmd("Target.activateTarget", json!({
+            "targetId": self.target_id
+        })).await?;
+
+        Ok(self)
+    }
+
+    /**
+    Navigate to a URL and wait for the page's `load` event.
+
+    Use [`goto_with_options`] to wait for another lifecycle event or change the timeout.
+
+    [`goto_with_options`]: struct.Tab.html#method.goto_with_options
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.goto("https://www.rust-lang.org/").await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn goto(&self, url: &str) -> Result<&Self> {
+        self.goto_with_options(url, NavigateOptions::default()).await
+    }
+
+    /**
+    Navigate to a URL and wait for the given lifecycle event.
+
+    Fails with [`Error::Navigation`] if the page cannot be loaded,
+    and with [`Error::Timeout`] if the event does not happen in time.
+
+    # Example
+    ```no_run
+    use std::time::Duration;
+    use cdp_html_shot::{Browser, NavigateOptions, WaitUntil};
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+
+        let options = NavigateOptions::new()
+            .with_wait_until(WaitUntil::NetworkIdle { idle_ms: 500, max_inflight: 0 })
+            .with_timeout(Duration::from_secs(10));
+        tab.goto_with_options("https://www.rust-lang.org/", options).await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn goto_with_options(&self, url: &str, options: NavigateOptions) -> Result<&Self> {
+        if options.wait_until != WaitUntil::None {
+            self.send_cmd("Page.enable", json!({})).await?;
+            self.send_cmd("Page.setLifecycleEventsEnabled", json!({
+                "enabled": true
+            })).await?;
+        }
+        if let WaitUntil::NetworkIdle { .. } = options.wait_until {
+            self.send_cmd("Network.enable", json!({})).await?;
+        }
+
+        // Subscribe before navigating, so no lifecycle event can be missed.
+        let mut events = self.transport.subscribe_session(&self.session_id).await?;
+
+        let res = self.send_cmd("Page.navigate", json!({
+            "url": url
+        })).await?;
+
+        if let Some(error) = res["errorText"].as_str().filter(|e| !e.is_empty()) {
+            return Err(Error::Navigation {
+                url: url.to_string(),
+                error: error.to_string(),
+            });
+        }
+
+        // Same-document navigations (e.g. to an anchor) have no loader and no lifecycle.
+        let (Some(frame_id), Some(loader_id)) = (res["frameId"].as_str(), res["loaderId"].as_str()) else {
+            return Ok(self);
+        };
+        if options.wait_until == WaitUntil::None {
+            return Ok(self);
+        }
+
+        let lifecycle = self.wait_for_lifecycle(&mut events, frame_id, loader_id, options.wait_until);
+        tokio::time::timeout(options.timeout, lifecycle)
+            .await
+            .map_err(|_| Error::Timeout { method: "Page.navigate".to_string() })??;
+
+        Ok(self)
+    }
+
+    async fn wait_for_lifecycle(
+        &self,
+        events: &mut mpsc::UnboundedReceiver<CdpEvent>,
+        frame_id: &str,
+        loader_id: &str,
+        wait_until: WaitUntil,
+    ) -> Result<()> {
+        let mut loaded = false;
+        let mut inflight = HashSet::new();
+        let mut idle_since: Option<Instant> = None;
+
+        loop {
+            let event = match (wait_until, idle_since) {
+                (WaitUntil::NetworkIdle { idle_ms, .. }, Some(since)) => {
+                    let deadline = since + Duration::from_millis(idle_ms);
+                    match tokio::time::timeout_at(deadline.into(), events.recv()).await {
+                        Ok(event) => event,
+                        Err(_) => return Ok(()),
+                    }
+                }
+                _ => events.recv().await,
+            };
+
+            let Some(event) = event else {
+                return Err(Error::ConnectionClosed);
+            };
+
+            match event.method.as_str() {
+                "Page.lifecycleEvent"
+                    if event.params["frameId"] == frame_id && event.params["loaderId"] == loader_id =>
+                {
+                    match (event.params["name"].as_str(), wait_until) {
+                        (Some("DOMContentLoaded"), WaitUntil::DomContentLoaded) => return Ok(()),
+                        (Some("load"), WaitUntil::Load) => return Ok(()),
+                        (Some("load"), _) => loaded = true,
+                        _ => {}
+                    }
+                }
+                "Network.requestWillBeSent" => {
+                    inflight.insert(event.params["requestId"].to_string());
+                }
+                "Network.loadingFinished" | "Network.loadingFailed" => {
+                    inflight.remove(&event.params["requestId"].to_string());
+                }
+                _ => {}
+            }
+
+            if let WaitUntil::NetworkIdle { max_inflight, .. } = wait_until {
+                idle_since = match (loaded && inflight.len() <= max_inflight, idle_since) {
+                    (true, Some(since)) => Some(since),
+                    (true, None) => Some(Instant::now()),
+                    (false, _) => None,
+                };
+            }
+        }
+    }
+
+    /**
+    Close the tab.
+
+    # Example
+    ```no_run
+    use cdp_html_shot::Browser;
+    use anyhow::Result;
+
+    #[tokio::main]
+    async fn main() -> Result<()> {
+        let browser = Browser::new().await?;
+        let tab = browser.new_tab().await?;
+        tab.close().await?;
+        Ok(())
+    }
+    ```
+    */
+    pub async fn close(&self) -> Result<()> {
+        self.send_cmd("Target.closeTarget", json!({
+            "targetId": self.target_id
+        })).await?;
 
         Ok(())
     }
@@ -2,16 +2,18 @@ use tokio::time;
 use time::Duration;
 use serde_json::Value;
 use futures_util::StreamExt;
-use anyhow::{anyhow, Result};
-use tokio::sync::{mpsc, oneshot};
-use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::http::HeaderMap;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use std::{
     collections::HashMap,
     sync::{Arc, Condvar, Mutex},
 };
 
-use crate::transport_actor::{TransportActor, TransportMessage, TransportResponse};
+use crate::error::{Error, Result};
+use crate::CdpEvent;
+use crate::transport_actor::{TransportActor, TransportMessage};
 
 #[derive(Debug)]
 pub(crate) struct ShutdownSignal {
@@ -41,31 +43,34 @@ impl ShutdownSignal {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct Response {
-    pub(crate) id: u64,
-    pub(crate) result: Value,
-}
+/// How long to wait for a reply to a command unless configured otherwise.
+pub(crate) const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug)]
 pub(crate) struct Transport {
     tx: mpsc::Sender<TransportMessage>,
-    shutdown_tx: Option<oneshot::Sender<()>>,
+    shutdown_tx: Mutex<Option<oneshot::Sender<bool>>>,
     shutdown_signal: Arc<ShutdownSignal>,
+    event_tx: broadcast::Sender<CdpEvent>,
+    command_timeout: Duration,
 }
 
 unsafe impl Send for Transport {}
 unsafe impl Sync for Transport {}
 
 impl Transport {
-    pub(crate) async fn new(ws_url: &str) -> Result<Self> {
-        let (ws_stream, _) = connect_async(ws_url).await?;
+    pub(crate) async fn new(ws_url: &str, headers: HeaderMap, command_timeout: Duration) -> Result<Self> {
+        let mut request = ws_url.into_client_request()?;
+        request.headers_mut().extend(headers);
+
+        let (ws_stream, _) = connect_async(request).await?;
         let (ws_sink, ws_stream) = ws_stream.split();
 
         let (tx, rx) = mpsc::channel::<TransportMessage>(100);
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
         let signal = Arc::new(ShutdownSignal::new());
         let signal_clone = signal.clone();
+        let (event_tx, _) = broadcast::channel(1024);
 
         let actor = TransportActor {
             pending_requests: HashMap::new(),
@@ -73,42 +78,85 @@ impl Transport {
             command_rx: rx,
             shutdown_rx,
             shutdown_signal: signal_clone,
+            event_tx: event_tx.clone(),
+            session_subscribers: Vec::new(),
         };
 
         tokio::spawn(actor.run(ws_stream));
 
-        Ok(Self { tx, shutdown_tx: Some(shutdown_tx), shutdown_signal: signal })
+        Ok(Self {
+            tx,
+            shutdown_tx: Mutex::new(Some(shutdown_tx)),
+            shutdown_signal: signal,
+            event_tx,
+            command_timeout,
+        })
     }
 
-    pub(crate) async fn send(&self, command: Value) -> Result<TransportResponse> {
-        let (response_tx, response_rx) = oneshot::channel();
+    /// Receive every CDP event sent after this call.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<CdpEvent> {
+        self.event_tx.subscribe()
+    }
 
-        self.tx.send(TransportMessage::Request(command, response_tx)).await?;
+    /// Receive every event of one target session sent after this call.
+    ///
+    /// Unlike [`subscribe`](Self::subscribe), events are never dropped when the receiver
+    /// falls behind, and events of other sessions do not take up any room.
+    pub(crate) async fn subscribe_session(&self, session_id: &str) -> Result<mpsc::UnboundedReceiver<CdpEvent>> {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
 
-        match time::timeout(Duration::from_secs(5), response_rx).await {
-            Ok(response) => response?,
-            Err(_) => Err(anyhow!("Timeout while waiting for response")),
-        }
+        self.tx
+            .send(TransportMessage::Subscribe(session_id.to_string(), event_tx))
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
+
+        Ok(event_rx)
+    }
+
+    /// The default time to wait for a reply to a command.
+    pub(crate) fn command_timeout(&self) -> Duration {
+        self.command_timeout
     }
 
-    pub(crate) async fn get_target_msg(&self, msg_id: usize) -> Result<TransportResponse> {
+    /// Send a command to the browser, or to a target when `session_id` is set,
+    /// and return its `result` object.
+    pub(crate) async fn send(&self, session_id: Option<&str>, command: Value, timeout: Duration) -> Result<Value> {
+        let method = command["method"].as_str().unwrap_or_default().to_string();
         let (response_tx, response_rx) = oneshot::channel();
 
-        self.tx.send(TransportMessage::ListenTargetMessage(msg_id as u64, response_tx)).await?;
+        self.tx
+            .send(TransportMessage::Request(session_id.map(String::from), command, response_tx))
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
 
-        match time::timeout(Duration::from_secs(5), response_rx).await {
-            Ok(response) => response?,
-            Err(_) => Err(anyhow!("Timeout while waiting for response")),
+        match time::timeout(timeout, response_rx).await {
+            Ok(response) => response.map_err(|_| Error::ConnectionClosed)?,
+            Err(_) => Err(Error::Timeout { method }),
         }
     }
 
-    pub(crate) fn shutdown(&mut self) {
-        self.shutdown_tx
-            .take()
-            .unwrap()
-            .send(())
-            .unwrap();
+    /// Send a browser-level command and return its `result` object.
+    pub(crate) async fn call(&self, command: Value) -> Result<Value> {
+        self.send(None, command, self.command_timeout).await
+    }
+
+    /// Close the browser and the connection to it.
+    pub(crate) fn shutdown(&self) {
+        self.stop(true);
+    }
+
+    /// Close the connection, leaving the browser running.
+    pub(crate) fn disconnect(&self) {
+        self.stop(false);
+    }
+
+    fn stop(&self, close_browser: bool) {
+        let Some(shutdown_tx) = self.shutdown_tx.lock().unwrap().take() else {
+            return;
+        };
 
-        self.shutdown_signal.wait();
+        if shutdown_tx.send(close_browser).is_ok() {
+            self.shutdown_signal.wait();
+        }
     }
 }
\ No newline at end of file
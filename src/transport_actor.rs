@@ -1,8 +1,6 @@
 use tokio::net::TcpStream;
-use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
-use tokio::sync::{mpsc, oneshot};
-use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use futures_util::{SinkExt, StreamExt};
 use futures_util::stream::{SplitSink, SplitStream};
 use std::{
@@ -16,34 +14,31 @@ use tokio_tungstenite::{
 };
 
 
+use crate::CdpEvent;
 use crate::general_utils;
+use crate::error::{Error, Result};
 use crate::general_utils::next_id;
-use crate::transport::{Response, ShutdownSignal};
+use crate::transport::ShutdownSignal;
 
 #[derive(Debug)]
 pub(crate) enum TransportMessage {
-    Request(Value, oneshot::Sender<Result<TransportResponse>>),
-    ListenTargetMessage(u64, oneshot::Sender<Result<TransportResponse>>),
+    /// A command for the browser (`None`) or for a flattened target session.
+    Request(Option<String>, Value, oneshot::Sender<Result<Value>>),
+    /// Deliver every event of a target session to the sender, without dropping any.
+    Subscribe(String, mpsc::UnboundedSender<CdpEvent>),
 }
 
-#[derive(Debug)]
-pub(crate) enum TransportResponse {
-    Response(Response),
-    Target(TargetMessage),
-}
+/// Pending replies, keyed by the session the command was sent to and its id.
+type PendingKey = (Option<String>, u64);
 
 pub(crate) struct TransportActor {
-    pub(crate) pending_requests: HashMap<u64, oneshot::Sender<Result<TransportResponse>>>,
+    pub(crate) pending_requests: HashMap<PendingKey, oneshot::Sender<Result<Value>>>,
     pub(crate) ws_sink: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
     pub(crate) command_rx: mpsc::Receiver<TransportMessage>,
-    pub(crate) shutdown_rx: oneshot::Receiver<()>,
+    pub(crate) shutdown_rx: oneshot::Receiver<bool>,
     pub(crate) shutdown_signal: Arc<ShutdownSignal>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct TargetMessage {
-    method: String,
-    pub(crate) params: Value,
+    pub(crate) event_tx: broadcast::Sender<CdpEvent>,
+    pub(crate) session_subscribers: Vec<(String, mpsc::UnboundedSender<CdpEvent>)>,
 }
 
 impl TransportActor {
@@ -54,15 +49,12 @@ impl TransportActor {
                 Some(msg) = ws_stream.next() => {
                     match msg {
                         Ok(Message::Text(text)) => {
-                            if let Ok(response) = serde_json::from_str::<Response>(&text) {
-                                self.handle_res(response).await;
-                            }
-                            if let Ok(target_msg) = serde_json::from_str::<TargetMessage>(&text) {
-                                self.handle_target_msg(target_msg).await;
+                            if let Ok(message) = serde_json::from_str::<Value>(&text) {
+                                self.handle_msg(message).await;
                             }
                         }
                         Err(e) => {
-                            self.handle_error(anyhow!("{e}")).await;
+                            self.handle_error(e.into()).await;
                             break;
                         }
                         _ => {}
@@ -71,24 +63,26 @@ impl TransportActor {
 
                 Some(msg) = self.command_rx.recv() => {
                     match msg {
-                        TransportMessage::Request(cmd, response_tx) => self.handle_req(cmd, response_tx).await,
-                        TransportMessage::ListenTargetMessage(msg_id, response_tx) => self.listen_target_msg(msg_id, response_tx).await,
+                        TransportMessage::Request(session_id, cmd, response_tx) => self.handle_req(session_id, cmd, response_tx).await,
+                        TransportMessage::Subscribe(session_id, event_tx) => self.session_subscribers.push((session_id, event_tx)),
                     };
                 }
 
-                _ = &mut self.shutdown_rx => {
-                    let command = json!({
-                            "id": next_id(),
-                            "method": "Browser.close",
-                            "params": {}
-                        });
+                close_browser = &mut self.shutdown_rx => {
+                    if close_browser.unwrap_or(true) {
+                        let command = json!({
+                                "id": next_id(),
+                                "method": "Browser.close",
+                                "params": {}
+                            });
 
-                    let msg = Message::Text(serde_json::to_string(&command).unwrap());
+                        let msg = Message::Text(command.to_string());
 
-                    let  _ = self.ws_sink
-                        .send(msg)
-                        .await
-                        .is_ok();
+                        let  _ = self.ws_sink
+                            .send(msg)
+                            .await
+                            .is_ok();
+                    }
 
                     let  _ = self.ws_sink
                         .close()
@@ -109,53 +103,74 @@ impl TransportActor {
 
     async fn handle_req(
         &mut self,
-        command: Value,
-        response_tx: oneshot::Sender<Result<TransportResponse>>,
+        session_id: Option<String>,
+        mut command: Value,
+        response_tx: oneshot::Sender<Result<Value>>,
     ) {
-        let message = Message::Text(serde_json::to_string(&command).unwrap());
+        let Some(id) = command["id"].as_u64() else {
+            let _ = response_tx.send(Err(Error::protocol("command without an id")));
+            return;
+        };
+
+        if let Some(session_id) = &session_id {
+            command["sessionId"] = json!(session_id);
+        }
+        let message = Message::Text(command.to_string());
 
         match self.ws_sink.send(message).await {
             Ok(_) => {
-                self.pending_requests.insert(command["id"].as_u64().unwrap(), response_tx);
+                self.pending_requests.insert((session_id, id), response_tx);
             }
             Err(e) => {
-                let _ = response_tx.send(Err(anyhow!("Connection error: {}", e)));
+                let _ = response_tx.send(Err(e.into()));
             }
         }
     }
 
-    async fn handle_res(&mut self, response: Response) {
-        if let Some(sender) = self.pending_requests.remove(&response.id) {
-            let _ = sender.send(Ok(TransportResponse::Response(response)));
-        }
-    }
+    /// Route a reply to the command waiting for it, or broadcast an event.
+    async fn handle_msg(&mut self, mut message: Value) {
+        let session_id = message["sessionId"].as_str().map(String::from);
 
-    async fn handle_target_msg(&mut self, msg: TargetMessage) {
-        if &msg.method != "Target.receivedMessageFromTarget" {
-            return;
-        }
-        let message = general_utils::serde_msg(&msg);
-        if message.get("id").is_none() {
-            return;
-        }
-        if let Some(sender) = self.pending_requests.remove(&message.get("id").unwrap().as_u64().unwrap()) {
-            let _ = sender.send(Ok(TransportResponse::Target(msg)));
+        match message["id"].as_u64() {
+            Some(id) => {
+                if let Some(sender) = self.pending_requests.remove(&(session_id, id)) {
+                    let _ = sender.send(general_utils::reply_result(message));
+                }
+            }
+            None => {
+                let Some(method) = message["method"].as_str().map(String::from) else {
+                    return;
+                };
+                let event = CdpEvent {
+                    session_id,
+                    method,
+                    params: message["params"].take(),
+                };
+
+                if let Some(session_id) = &event.session_id {
+                    // Subscribers whose receiver was dropped are removed.
+                    self.session_subscribers.retain(|(id, tx)| match id == session_id {
+                        true => tx.send(event.clone()).is_ok(),
+                        false => !tx.is_closed(),
+                    });
+                }
+
+                // Nobody listening is not an error.
+                let _ = self.event_tx.send(event);
+            }
         }
     }
 
-    async fn handle_error(&mut self, error: anyhow::Error) {
+    async fn handle_error(&mut self, error: Error) {
+        log::error!("WebSocket connection failed: {error}");
         for (_, sender) in self.pending_requests.drain() {
-            let _ = sender.send(Err(anyhow!("Connection error: {}", error)));
+            let _ = sender.send(Err(Error::ConnectionClosed));
         }
     }
 
     async fn cleanup(&mut self) {
         for (_, sender) in self.pending_requests.drain() {
-            let _ = sender.send(Err(anyhow!("Connection closed")));
+            let _ = sender.send(Err(Error::ConnectionClosed));
         }
     }
-
-    async fn listen_target_msg(&mut self, msg_id: u64, response_tx: oneshot::Sender<Result<TransportResponse>>) {
-        self.pending_requests.insert(msg_id, response_tx);
-    }
-}
\ No newline at end of file
+}
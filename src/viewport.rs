@@ -0,0 +1,24 @@
+/// The emulated viewport of a tab, in CSS pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The viewport width.
+    pub width: u32,
+    /// The viewport height.
+    pub height: u32,
+    /// Whether to emulate a mobile device (meta viewport, overlay scrollbars).
+    pub mobile: bool,
+    /// The device scale factor (device pixel ratio).
+    pub scale: f64,
+}
+
+impl Viewport {
+    /// Create a desktop viewport of the given size with a scale factor of `1.0`.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            mobile: false,
+            scale: 1.0,
+        }
+    }
+}
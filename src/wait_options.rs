@@ -0,0 +1,95 @@
+use std::fmt;
+use std::time::Duration;
+
+/// The state of an element that [`Tab::wait_for_selector`](crate::Tab::wait_for_selector) waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ElementState {
+    /// The element is present in the DOM.
+    Attached,
+    /// The element is present and visible: it has a non-empty box
+    /// and is not hidden by `display` or `visibility`.
+    #[default]
+    Visible,
+    /// The element is either not present or not visible.
+    Hidden,
+    /// The element is not present in the DOM.
+    Detached,
+}
+
+impl ElementState {
+    /// Parse an observed state reported by the page probe.
+    pub(crate) fn from_probe(state: &str) -> Self {
+        match state {
+            "visible" => ElementState::Visible,
+            "hidden" => ElementState::Hidden,
+            _ => ElementState::Detached,
+        }
+    }
+
+    /// Whether an element observed in state `observed` satisfies this wait condition.
+    pub(crate) fn is_satisfied_by(&self, observed: ElementState) -> bool {
+        match self {
+            ElementState::Attached => observed != ElementState::Detached,
+            ElementState::Visible => observed == ElementState::Visible,
+            ElementState::Hidden => observed != ElementState::Visible,
+            ElementState::Detached => observed == ElementState::Detached,
+        }
+    }
+}
+
+impl fmt::Display for ElementState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ElementState::Attached => "attached",
+            ElementState::Visible => "visible",
+            ElementState::Hidden => "hidden",
+            ElementState::Detached => "detached",
+        })
+    }
+}
+
+/// Configuration options for waiting on an element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaitOptions {
+    /// The state to wait for.
+    pub state: ElementState,
+    /// How long to wait before giving up.
+    pub timeout: Duration,
+    /// How often to check the element.
+    pub poll: Duration,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            state: ElementState::Visible,
+            timeout: Duration::from_secs(30),
+            poll: Duration::from_millis(100),
+        }
+    }
+}
+
+impl WaitOptions {
+    /// Create new wait options with default values (visible, 30 s timeout, 100 ms poll).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the state to wait for.
+    pub fn with_state(mut self, state: ElementState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Set how long to wait before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how often to check the element.
+    pub fn with_poll(mut self, poll: Duration) -> Self {
+        self.poll = poll;
+        self
+    }
+}